@@ -0,0 +1,228 @@
+use crate::{Config, ConfigError, OutputDataRate, Oversampling};
+
+/// Rate to solve the oversampling for
+#[derive(Debug, Copy, Clone)]
+enum Rate {
+    OutputDataRate(OutputDataRate),
+    MinHz(f32),
+}
+
+/// Builds a [`Config`] with the highest oversampling that fits a target rate
+///
+/// Pressure oversampling is maximized first, then temperature oversampling
+/// up to the pressure one, both within the conversion time budget of the
+/// output data rate and the optional noise and current limits. Other
+/// settings are taken from a base configuration.
+#[derive(Debug, Copy, Clone)]
+pub struct ConfigBuilder {
+    base: Config,
+    rate: Rate,
+    max_noise_pa: Option<f32>,
+    max_current_ua: Option<f32>,
+}
+
+impl ConfigBuilder {
+    /// Target the given output data rate.
+    pub fn new(output_data_rate: OutputDataRate) -> Self {
+        Self {
+            base: Config::default(),
+            rate: Rate::OutputDataRate(output_data_rate),
+            max_noise_pa: None,
+            max_current_ua: None,
+        }
+    }
+
+    /// Target the slowest output data rate of at least `hz`.
+    pub fn min_rate_hz(hz: f32) -> Self {
+        Self {
+            rate: Rate::MinHz(hz),
+            ..Self::new(OutputDataRate::default())
+        }
+    }
+
+    /// Take the settings other than oversampling and output data rate from
+    /// `base`.
+    pub fn base(mut self, base: Config) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Limit the RMS pressure noise, before IIR filtering.
+    pub fn max_noise_pa(mut self, max_noise_pa: f32) -> Self {
+        self.max_noise_pa = Some(max_noise_pa);
+        self
+    }
+
+    /// Limit the estimated average current in normal mode, see
+    /// [`Config::estimated_current_ua`].
+    pub fn max_current_ua(mut self, max_current_ua: f32) -> Self {
+        self.max_current_ua = Some(max_current_ua);
+        self
+    }
+
+    /// Solve for the oversampling.
+    ///
+    /// The result is checked with [`Config::validate`], so an invalid base
+    /// is reported as well.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let output_data_rate = match self.rate {
+            Rate::OutputDataRate(output_data_rate) => output_data_rate,
+            Rate::MinHz(hz) => slowest_rate(hz).ok_or(ConfigError::RateUnavailable)?,
+        };
+
+        for pressure_code in (0..=7).rev() {
+            let pressure = oversampling(pressure_code);
+            if self
+                .max_noise_pa
+                .is_some_and(|max| pressure.pressure_noise_pa() > max)
+            {
+                // Lower oversampling is only noisier
+                break;
+            }
+
+            for temperature_code in (0..=pressure_code).rev() {
+                let config = Config {
+                    temperature_oversampling: oversampling(temperature_code),
+                    pressure_oversampling: pressure,
+                    output_data_rate,
+                    ..self.base
+                };
+                let fits = config.conversion_time_us() <= output_data_rate.period_us();
+                let current_ok = self
+                    .max_current_ua
+                    .is_none_or(|max| config.estimated_current_ua() <= max);
+                if fits && current_ok {
+                    // The base may still be invalid
+                    config.validate()?;
+                    return Ok(config);
+                }
+            }
+        }
+        Err(ConfigError::NoOversamplingFits)
+    }
+}
+
+fn oversampling(code: u8) -> Oversampling {
+    // All 3 bit codes are valid
+    Oversampling::try_from(code).unwrap_or_default()
+}
+
+/// Slowest output data rate of at least `hz`.
+fn slowest_rate(hz: f32) -> Option<OutputDataRate> {
+    // Codes go from 240 Hz (0x00) down to 0.125 Hz (0x1F)
+    (0..=0x1F).rev().find_map(|code| {
+        let output_data_rate = OutputDataRate::try_from(code).ok()?;
+        // Periods are rounded to the microsecond
+        let period_us = output_data_rate.period_us() - 1;
+        (period_us as f32 * hz <= 1_000_000.0).then_some(output_data_rate)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fifo::{FifoConfig, FifoFrameSelection};
+
+    #[test]
+    fn slowest_rate_boundaries() {
+        assert_eq!(slowest_rate(1.0), Some(OutputDataRate::OutputDataRate1Hz));
+        assert_eq!(
+            slowest_rate(0.125),
+            Some(OutputDataRate::OutputDataRate0_125Hz)
+        );
+        assert_eq!(
+            slowest_rate(0.01),
+            Some(OutputDataRate::OutputDataRate0_125Hz)
+        );
+        assert_eq!(slowest_rate(1.5), Some(OutputDataRate::OutputDataRate2Hz));
+        assert_eq!(
+            slowest_rate(240.0),
+            Some(OutputDataRate::OutputDataRate240Hz)
+        );
+        assert_eq!(slowest_rate(240.5), None);
+        assert_eq!(
+            ConfigBuilder::min_rate_hz(300.0).build(),
+            Err(ConfigError::RateUnavailable)
+        );
+    }
+
+    #[test]
+    fn rate_limited() {
+        let config = ConfigBuilder::new(OutputDataRate::OutputDataRate50Hz)
+            .build()
+            .unwrap();
+        assert_eq!(config.pressure_oversampling, Oversampling::Oversampling16x);
+        assert_eq!(
+            config.temperature_oversampling,
+            Oversampling::Oversampling16x
+        );
+
+        // Pressure is maximized before temperature
+        let config = ConfigBuilder::new(OutputDataRate::OutputDataRate10Hz)
+            .build()
+            .unwrap();
+        assert_eq!(config.pressure_oversampling, Oversampling::Oversampling128x);
+        assert_eq!(
+            config.temperature_oversampling,
+            Oversampling::Oversampling32x
+        );
+    }
+
+    #[test]
+    fn noise_limited() {
+        let config = ConfigBuilder::new(OutputDataRate::OutputDataRate50Hz)
+            .max_noise_pa(0.25)
+            .build()
+            .unwrap();
+        assert_eq!(config.pressure_oversampling, Oversampling::Oversampling16x);
+
+        // 64x and up don't fit into the period, 32x is too noisy
+        assert_eq!(
+            ConfigBuilder::new(OutputDataRate::OutputDataRate50Hz)
+                .max_noise_pa(0.1)
+                .build(),
+            Err(ConfigError::NoOversamplingFits)
+        );
+    }
+
+    #[test]
+    fn current_limited() {
+        let config = ConfigBuilder::new(OutputDataRate::OutputDataRate1Hz)
+            .max_current_ua(20.0)
+            .build()
+            .unwrap();
+        assert_eq!(config.pressure_oversampling, Oversampling::Oversampling32x);
+        assert_eq!(
+            config.temperature_oversampling,
+            Oversampling::Oversampling32x
+        );
+        assert!(config.estimated_current_ua() <= 20.0);
+
+        // Only 32x and below are within the current, but too noisy
+        assert_eq!(
+            ConfigBuilder::new(OutputDataRate::OutputDataRate1Hz)
+                .max_current_ua(20.0)
+                .max_noise_pa(0.1)
+                .build(),
+            Err(ConfigError::NoOversamplingFits)
+        );
+    }
+
+    #[test]
+    fn invalid_base() {
+        let base = Config {
+            fifo: FifoConfig {
+                frame_selection: FifoFrameSelection::PressureAndTemperature,
+                threshold: 16,
+                ..FifoConfig::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(
+            ConfigBuilder::new(OutputDataRate::OutputDataRate1Hz)
+                .base(base)
+                .build(),
+            Err(ConfigError::FifoThresholdTooHigh)
+        );
+    }
+}
@@ -51,6 +51,7 @@ pub(crate) const BMP5_ODR_DEEPSLEEP_DIS: u8 = 0x80;
 
 // ODR settings
 pub(crate) const BMP5_ODR_MASK: u8 = 0x7C;
+pub(crate) const BMP5_ODR_POS: u8 = 2;
 pub(crate) const BMP5_ODR_240_HZ: u8 = 0x00;
 pub(crate) const BMP5_ODR_218_5_HZ: u8 = 0x01;
 pub(crate) const BMP5_ODR_199_1_HZ: u8 = 0x02;
@@ -86,6 +87,9 @@ pub(crate) const BMP5_ODR_0_125_HZ: u8 = 0x1F;
 
 // Oversampling for temperature and pressure
 pub(crate) const BMP5_OSR_MASK: u8 = 0x3F;
+pub(crate) const BMP5_OSR_TEMP_MASK: u8 = 0x07;
+pub(crate) const BMP5_OSR_PRESS_MASK: u8 = 0x38;
+pub(crate) const BMP5_OSR_PRESS_POS: u8 = 3;
 pub(crate) const BMP5_OSR_1X: u8 = 0x00;
 pub(crate) const BMP5_OSR_2X: u8 = 0x01;
 pub(crate) const BMP5_OSR_4X: u8 = 0x02;
@@ -99,6 +103,10 @@ pub(crate) const BMP5_OSR_PRESS_EN: u8 = 0x40;
 pub(crate) const BMP5_OSR_EFF_VALID_ODR: u8 = 0x80;
 
 // IIR filter for temperature and pressure
+pub(crate) const BMP5_IIR_FILTER_MASK: u8 = 0x3F;
+pub(crate) const BMP5_IIR_FILTER_TEMP_MASK: u8 = 0x07;
+pub(crate) const BMP5_IIR_FILTER_PRESS_MASK: u8 = 0x38;
+pub(crate) const BMP5_IIR_FILTER_PRESS_POS: u8 = 3;
 pub(crate) const BMP5_IIR_FILTER_BYPASS: u8 = 0x00;
 pub(crate) const BMP5_IIR_FILTER_COEFF_1: u8 = 0x01;
 pub(crate) const BMP5_IIR_FILTER_COEFF_3: u8 = 0x02;
@@ -108,10 +116,61 @@ pub(crate) const BMP5_IIR_FILTER_COEFF_31: u8 = 0x05;
 pub(crate) const BMP5_IIR_FILTER_COEFF_63: u8 = 0x06;
 pub(crate) const BMP5_IIR_FILTER_COEFF_127: u8 = 0x07;
 
+// FIFO configuration
+pub(crate) const BMP5_FIFO_THRESHOLD_MASK: u8 = 0x1F;
+pub(crate) const BMP5_FIFO_MAX_THRESHOLD: u8 = 0x1F;
+pub(crate) const BMP5_FIFO_MAX_THRESHOLD_PRESS_TEMP: u8 = 0x0F;
+pub(crate) const BMP5_FIFO_MODE_STOP_ON_FULL: u8 = 0x20;
+pub(crate) const BMP5_FIFO_COUNT_MASK: u8 = 0x3F;
+pub(crate) const BMP5_FIFO_EMPTY: u8 = 0x7F;
+
+// FIFO frame selection
+pub(crate) const BMP5_FIFO_FRAME_SEL_MASK: u8 = 0x03;
+pub(crate) const BMP5_FIFO_FRAME_SEL_DISABLED: u8 = 0x00;
+pub(crate) const BMP5_FIFO_FRAME_SEL_TEMP: u8 = 0x01;
+pub(crate) const BMP5_FIFO_FRAME_SEL_PRESS: u8 = 0x02;
+pub(crate) const BMP5_FIFO_FRAME_SEL_PRESS_TEMP: u8 = 0x03;
+pub(crate) const BMP5_FIFO_DEC_SEL_MASK: u8 = 0x1C;
+pub(crate) const BMP5_FIFO_DEC_SEL_POS: u8 = 2;
+
+// Pressure out-of-range configuration
+pub(crate) const BMP5_OOR_MAX_THRESHOLD: u32 = 0x1FFFF;
+pub(crate) const BMP5_OOR_THR_P_XMSB_MASK: u8 = 0x01;
+pub(crate) const BMP5_OOR_COUNT_LIMIT_MASK: u8 = 0xC0;
+pub(crate) const BMP5_OOR_COUNT_LIMIT_POS: u8 = 6;
+
+// Chip status register
+pub(crate) const BMP5_CHIP_STATUS_HIF_MODE_MASK: u8 = 0x03;
+pub(crate) const BMP5_CHIP_STATUS_I3C_ERR_0: u8 = 0x04;
+pub(crate) const BMP5_CHIP_STATUS_I3C_ERR_3: u8 = 0x08;
+
+// Status register
+pub(crate) const BMP5_STATUS_CORE_RDY: u8 = 0x01;
+pub(crate) const BMP5_STATUS_NVM_RDY: u8 = 0x02;
+pub(crate) const BMP5_STATUS_NVM_ERR: u8 = 0x04;
+pub(crate) const BMP5_STATUS_NVM_CMD_ERR: u8 = 0x08;
+
+// NVM access
+pub(crate) const BMP5_NVM_ADDR_MASK: u8 = 0x3F;
+pub(crate) const BMP5_NVM_PROG_EN: u8 = 0x40;
+pub(crate) const BMP5_NVM_READ_TIME_US: u32 = 800;
+pub(crate) const BMP5_NVM_WRITE_TIME_US: u32 = 10000;
+pub(crate) const BMP5_NVM_START_ADDR: u8 = 0x20;
+pub(crate) const BMP5_NVM_END_ADDR: u8 = 0x22;
+
 // Chip id of BMP5x
 pub(crate) const BMP5_CHIP_ID: u8 = 0x50;
 pub(crate) const BMP5_CHIP_ID_ALT: u8 = 0x51;
 
+// Interrupt pin configuration
+pub(crate) const BMP5_INT_CONFIG_MODE_LATCHED: u8 = 0x01;
+pub(crate) const BMP5_INT_CONFIG_POL_ACTIVE_HIGH: u8 = 0x02;
+pub(crate) const BMP5_INT_CONFIG_OPEN_DRAIN: u8 = 0x04;
+pub(crate) const BMP5_INT_CONFIG_EN: u8 = 0x08;
+pub(crate) const BMP5_INT_CONFIG_PAD_DRV_MASK: u8 = 0xF0;
+pub(crate) const BMP5_INT_CONFIG_PAD_DRV_POS: u8 = 4;
+pub(crate) const BMP5_INT_CONFIG_MAX_PAD_DRV: u8 = 0x0F;
+
 // Interrupt sources
 pub(crate) const BMP5_INT_ENABLE_DRDY: u8 = 0x01;
 pub(crate) const BMP5_INT_ENABLE_FIFO_FULL: u8 = 0x02;
@@ -124,3 +183,22 @@ pub(crate) const BMP5_INT_STATUS_FIFO_FULL: u8 = 0x02;
 pub(crate) const BMP5_INT_STATUS_FIFO_THRES: u8 = 0x04;
 pub(crate) const BMP5_INT_STATUS_PRESSURE_OOR: u8 = 0x08;
 pub(crate) const BMP5_INT_STATUS_POR_SOFTRESET_COMPLETE: u8 = 0x10;
+
+// SPI register address masks
+pub(crate) const BMP5_SPI_RD_MASK: u8 = 0x80;
+pub(crate) const BMP5_SPI_WR_MASK: u8 = 0x7F;
+
+// Conversion time model (microseconds). The datasheet oversampling table
+// lists maximum rates with temperature at 1x only, 498 Hz at 1x down to 12 Hz
+// at 128x, which fixes the pressure term and the sum of the base and
+// temperature terms. The split between those two is an estimate, so times
+// with temperature oversampling above 1x are extrapolated.
+pub(crate) const BMP5_CONV_TIME_BASE_US: u32 = 1000;
+pub(crate) const BMP5_CONV_TIME_PRESS_US: u32 = 630;
+pub(crate) const BMP5_CONV_TIME_TEMP_US: u32 = 380;
+
+// Current drawn while converting (microamperes)
+pub(crate) const BMP5_ACTIVE_CURRENT_UA: f32 = 600.0;
+
+// Default timeout, in ODR periods or forced conversion times
+pub(crate) const BMP5_TIMEOUT_PERIODS: u32 = 4;
@@ -0,0 +1,87 @@
+use crate::{Error, OversamplingSettings, PowerMode, constants, driver, interface};
+
+/// Host interface mode detected by the sensor
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterfaceMode {
+    /// I2C
+    I2c,
+    /// SPI mode 1 or 2
+    SpiMode1Or2,
+    /// SPI mode 0 or 3
+    SpiMode0Or3,
+    /// I3C
+    I3c,
+}
+
+/// Sensor health report
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Diagnostics {
+    /// Host interface mode
+    pub interface_mode: InterfaceMode,
+    /// I3C SDR parity error
+    pub i3c_parity_error: bool,
+    /// I3C S0/S1 error
+    pub i3c_s0_s1_error: bool,
+    /// Digital core ready
+    pub core_ready: bool,
+    /// NVM ready for an operation
+    pub nvm_ready: bool,
+    /// Last NVM operation failed
+    pub nvm_error: bool,
+    /// Last NVM command sequence was rejected
+    pub nvm_command_error: bool,
+    /// Oversampling the sensor actually applies
+    pub effective_oversampling: OversamplingSettings,
+    /// Oversampling and output data rate can be sustained
+    pub odr_valid: bool,
+    /// Current power mode
+    pub power_mode: PowerMode,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(
+            asynch(sync = "blocking"),
+            embedded_hal_async(sync = "embedded_hal"),
+            Wait(sync = "InputPin")
+        )
+    ),
+    async(keep_self)
+)]
+impl<I, D, P, E> driver::asynch::Bmp5<I, D, P>
+where
+    I: interface::asynch::RegisterInterface<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    P: embedded_hal_async::digital::Wait,
+{
+    /// Collect the status registers into a health report.
+    pub async fn diagnostics(&mut self) -> Result<Diagnostics, Error<E>> {
+        let chip_status = self.read_reg(constants::BMP5_REG_CHIP_STATUS).await?;
+        let status = self.read_reg(constants::BMP5_REG_STATUS).await?;
+        let osr_eff = self.read_reg(constants::BMP5_REG_OSR_EFF).await?;
+        let power_mode = self.power_mode().await?;
+
+        let interface_mode = match chip_status & constants::BMP5_CHIP_STATUS_HIF_MODE_MASK {
+            0 => InterfaceMode::I2c,
+            1 => InterfaceMode::SpiMode1Or2,
+            2 => InterfaceMode::SpiMode0Or3,
+            _ => InterfaceMode::I3c,
+        };
+
+        Ok(Diagnostics {
+            interface_mode,
+            i3c_parity_error: chip_status & constants::BMP5_CHIP_STATUS_I3C_ERR_0 != 0,
+            i3c_s0_s1_error: chip_status & constants::BMP5_CHIP_STATUS_I3C_ERR_3 != 0,
+            core_ready: status & constants::BMP5_STATUS_CORE_RDY != 0,
+            nvm_ready: status & constants::BMP5_STATUS_NVM_RDY != 0,
+            nvm_error: status & constants::BMP5_STATUS_NVM_ERR != 0,
+            nvm_command_error: status & constants::BMP5_STATUS_NVM_CMD_ERR != 0,
+            effective_oversampling: OversamplingSettings::from_bits(osr_eff),
+            odr_valid: osr_eff & constants::BMP5_OSR_EFF_VALID_ODR != 0,
+            power_mode,
+        })
+    }
+}
@@ -0,0 +1,1160 @@
+#[maybe_async_cfg::maybe(
+    sync(
+        self = "blocking",
+        idents(embedded_hal_async(sync = "embedded_hal"), Wait(sync = "InputPin"))
+    ),
+    async(keep_self)
+)]
+pub mod asynch {
+    use crate::fifo::{FifoConfig, FifoDecimation, FifoFrameSelection, FifoMode};
+    use crate::interface::asynch::RegisterInterface;
+    use crate::oor::{OorConfig, OorCountLimit};
+    use crate::{
+        ChipInfo, ChipVariant, Config, Error, IIRFilter, InterruptSource, InterruptStatus,
+        Measurement, NoPin, OutputDataRate, OversamplingSettings, PowerMode, constants,
+    };
+
+    /// BMP5 driver
+    pub struct Bmp5<I, D, P = NoPin> {
+        pub(crate) interface: I,
+        pub(crate) delay: D,
+        pub(crate) config: Config,
+        pub(crate) int_pin: Option<P>,
+        pub(crate) pending_interrupts: InterruptStatus,
+        pub(crate) requested_mode: PowerMode,
+        /// The sensor reported an unexpected reset, the configuration still
+        /// needs to be restored
+        pub(crate) reset_detected: bool,
+    }
+
+    impl<I, D, E> Bmp5<I, D, NoPin>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+    {
+        /// Create a driver on top of an arbitrary register interface.
+        pub fn from_interface(interface: I, delay: D, config: Config) -> Self {
+            Self {
+                interface,
+                delay,
+                config,
+                int_pin: None,
+                pending_interrupts: InterruptStatus::empty(),
+                requested_mode: PowerMode::Standby,
+                reset_detected: false,
+            }
+        }
+
+        /// Wait on the given input pin, connected to the INT pin of the sensor,
+        /// instead of polling the interrupt status register.
+        ///
+        /// The pin can be owned or borrowed (`&mut` pin). This enables the INT
+        /// pin in the configuration, with the mode, polarity and drive from
+        /// [`Config::interrupt_pin`]. It is written by `init`, an already
+        /// initialized driver needs
+        /// [`set_interrupt_pin_config`](Self::set_interrupt_pin_config).
+        pub fn with_interrupt_pin<P>(mut self, pin: P) -> Bmp5<I, D, P>
+        where
+            P: embedded_hal_async::digital::Wait,
+        {
+            self.config.interrupt_pin.enabled = true;
+            Bmp5 {
+                interface: self.interface,
+                delay: self.delay,
+                config: self.config,
+                int_pin: Some(pin),
+                pending_interrupts: self.pending_interrupts,
+                requested_mode: self.requested_mode,
+                reset_detected: self.reset_detected,
+            }
+        }
+    }
+
+    impl<I, D, P, E> Bmp5<I, D, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+    {
+        pub async fn init(&mut self) -> Result<(), Error<E>> {
+            // Over SPI the sensor powers up in I2C mode, a dummy read switches
+            // it to SPI. This is harmless when connected over I2C.
+            self.read_reg(constants::BMP5_REG_CHIP_ID).await?;
+
+            self.soft_reset().await?;
+            self.verify_chip_id().await?;
+            self.configure().await?;
+            Ok(())
+        }
+
+        /// Wait for the next sample, giving up after
+        /// [`Config::timeout_us`](crate::Config::timeout_us).
+        pub async fn measure(&mut self) -> Result<Measurement, Error<E>> {
+            let timeout_us = self.config.timeout_us.unwrap_or(
+                self.config.output_data_rate.period_us() * constants::BMP5_TIMEOUT_PERIODS,
+            );
+            self.measure_with_timeout(timeout_us).await
+        }
+
+        /// Wait for the next sample, giving up with [`Error::Timeout`] after
+        /// `timeout_us` microseconds.
+        pub async fn measure_with_timeout(
+            &mut self,
+            timeout_us: u32,
+        ) -> Result<Measurement, Error<E>> {
+            // Wait for the next sample to be ready.
+            self.wait_for_interrupt(
+                InterruptStatus::DATA_READY,
+                self.config.output_data_rate.period_us() / 4,
+                Some(timeout_us),
+            )
+            .await?;
+
+            self.read_measurement().await
+        }
+
+        /// Take a single measurement in forced mode.
+        ///
+        /// The sensor is put into standby, a single conversion is triggered and
+        /// the result is read back. Once the conversion completes the sensor
+        /// returns to standby on its own, so it only draws sleep current
+        /// between calls.
+        pub async fn measure_forced(&mut self) -> Result<Measurement, Error<E>> {
+            self.start_forced().await?;
+            self.finish_forced().await
+        }
+
+        pub(crate) async fn start_forced(&mut self) -> Result<(), Error<E>> {
+            // Enter standby mode, forced conversions can only be triggered from it
+            self.write_power_mode(PowerMode::Standby).await?;
+
+            // Discard any stale data ready flag
+            self.fetch_interrupt_status().await?;
+            if self.reset_detected {
+                // A conversion with the reset configuration would never raise
+                // data ready
+                self.reset_detected = false;
+                if let Err(err) = self.restore_config().await {
+                    self.reset_detected = true;
+                    return Err(err);
+                }
+                self.write_power_mode(PowerMode::Standby).await?;
+                self.fetch_interrupt_status().await?;
+            }
+            self.pending_interrupts.remove(InterruptStatus::DATA_READY);
+
+            // Trigger a single conversion
+            self.write_power_mode(PowerMode::Forced).await
+        }
+
+        pub(crate) async fn finish_forced(&mut self) -> Result<Measurement, Error<E>> {
+            let conversion_time_us = self.config.conversion_time_us();
+            let timeout_us = self
+                .config
+                .timeout_us
+                .unwrap_or(conversion_time_us * constants::BMP5_TIMEOUT_PERIODS);
+            self.delay.delay_us(conversion_time_us).await;
+            self.wait_for_interrupt(
+                InterruptStatus::DATA_READY,
+                conversion_time_us / 4,
+                Some(timeout_us.saturating_sub(conversion_time_us)),
+            )
+            .await?;
+
+            // The sensor is back in standby on its own
+            self.requested_mode = PowerMode::Standby;
+            self.read_measurement().await
+        }
+
+        /// Read the current power mode from the sensor.
+        pub async fn power_mode(&mut self) -> Result<PowerMode, Error<E>> {
+            let odr_config = self.read_reg(constants::BMP5_REG_ODR_CONFIG).await?;
+            let mode = match odr_config & constants::BMP5_MODE_MASK {
+                constants::BMP5_MODE_NORMAL => PowerMode::Normal,
+                constants::BMP5_MODE_FORCED => PowerMode::Forced,
+                constants::BMP5_MODE_CONTINOUS => PowerMode::Continuous,
+                _ if odr_config & constants::BMP5_ODR_DEEPSLEEP_DIS != 0 => PowerMode::Standby,
+                _ => {
+                    // With deep standby enabled the sensor only enters it if the
+                    // ODR is 5 Hz or below, and the FIFO and IIR filters are off.
+                    let odr = (odr_config & constants::BMP5_ODR_MASK) >> constants::BMP5_ODR_POS;
+                    let fifo_sel = self.read_reg(constants::BMP5_REG_FIFO_SEL).await?;
+                    let dsp_iir = self.read_reg(constants::BMP5_REG_DSP_IIR).await?;
+
+                    if odr >= constants::BMP5_ODR_05_HZ
+                        && fifo_sel & constants::BMP5_FIFO_FRAME_SEL_MASK == 0
+                        && dsp_iir & constants::BMP5_IIR_FILTER_MASK == 0
+                    {
+                        PowerMode::DeepStandby
+                    } else {
+                        PowerMode::Standby
+                    }
+                }
+            };
+            Ok(mode)
+        }
+
+        /// Change the power mode of the sensor.
+        ///
+        /// The sensor is always taken through standby first, as required by the
+        /// datasheet. Entering deep standby lowers the ODR to 1 Hz and bypasses the
+        /// IIR filters and FIFO, leaving it restores the configured ODR, filters
+        /// and FIFO.
+        pub async fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Error<E>> {
+            self.recover_if_reset().await?;
+            self.write_power_mode(mode).await
+        }
+
+        async fn write_power_mode(&mut self, mode: PowerMode) -> Result<(), Error<E>> {
+            self.requested_mode = mode;
+
+            let current = self.power_mode().await?;
+            if current != PowerMode::Standby {
+                self.enter_standby().await?;
+
+                if current == PowerMode::DeepStandby {
+                    self.write_iir_config().await?;
+                    self.write_odr_config().await?;
+                    self.write_fifo_config().await?;
+                }
+            }
+
+            match mode {
+                PowerMode::Standby => {}
+                PowerMode::DeepStandby => {
+                    // Enable deep standby with a 1 Hz ODR
+                    self.update_reg(
+                        constants::BMP5_REG_ODR_CONFIG,
+                        constants::BMP5_ODR_DEEPSLEEP_DIS | constants::BMP5_ODR_MASK,
+                        constants::BMP5_ODR_01_HZ << constants::BMP5_ODR_POS,
+                    )
+                    .await?;
+
+                    // Bypass the IIR filters
+                    self.update_reg(
+                        constants::BMP5_REG_DSP_IIR,
+                        constants::BMP5_IIR_FILTER_MASK,
+                        constants::BMP5_IIR_FILTER_BYPASS,
+                    )
+                    .await?;
+
+                    // Disable the FIFO
+                    self.update_reg(
+                        constants::BMP5_REG_FIFO_SEL,
+                        constants::BMP5_FIFO_FRAME_SEL_MASK,
+                        constants::BMP5_FIFO_FRAME_SEL_DISABLED,
+                    )
+                    .await?;
+                }
+                PowerMode::Normal => {
+                    self.update_reg(
+                        constants::BMP5_REG_ODR_CONFIG,
+                        constants::BMP5_MODE_MASK,
+                        constants::BMP5_MODE_NORMAL,
+                    )
+                    .await?;
+                }
+                PowerMode::Forced => {
+                    self.update_reg(
+                        constants::BMP5_REG_ODR_CONFIG,
+                        constants::BMP5_MODE_MASK,
+                        constants::BMP5_MODE_FORCED,
+                    )
+                    .await?;
+                }
+                PowerMode::Continuous => {
+                    self.update_reg(
+                        constants::BMP5_REG_ODR_CONFIG,
+                        constants::BMP5_MODE_MASK,
+                        constants::BMP5_MODE_CONTINOUS,
+                    )
+                    .await?;
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Put the sensor into standby so registers that are only writable
+        /// there can be updated. Returns the mode to hand back to
+        /// [`resume_after_update`](Self::resume_after_update).
+        pub(crate) async fn suspend_for_update(&mut self) -> Result<PowerMode, Error<E>> {
+            let mode = self.requested_mode;
+            if let Err(err) = self.set_power_mode(PowerMode::Standby).await {
+                self.requested_mode = mode;
+                return Err(err);
+            }
+            Ok(mode)
+        }
+
+        /// Return to the mode saved by
+        /// [`suspend_for_update`](Self::suspend_for_update), even if the update
+        /// failed. The update error takes precedence over the restore error.
+        pub(crate) async fn resume_after_update<T>(
+            &mut self,
+            mode: PowerMode,
+            result: Result<T, Error<E>>,
+        ) -> Result<T, Error<E>> {
+            let restored = match mode {
+                // The forced conversion has been aborted
+                PowerMode::Forced => {
+                    self.requested_mode = PowerMode::Standby;
+                    Ok(())
+                }
+                mode => self.set_power_mode(mode).await,
+            };
+
+            let value = result?;
+            restored?;
+            Ok(value)
+        }
+
+        pub async fn soft_reset(&mut self) -> Result<(), Error<E>> {
+            self.write_reg(constants::BMP5_REG_CMD, constants::BMP5_CMD_SOFT_RESET)
+                .await?;
+            self.delay.delay_us(2500).await;
+
+            // The reset puts the sensor back into I2C mode, a dummy read is
+            // required to switch it to SPI again.
+            self.read_reg(constants::BMP5_REG_CHIP_ID).await?;
+
+            // Acknowledge the reset complete flag, so it isn't mistaken for an
+            // unexpected reset later on. Earlier flags are stale now.
+            self.read_reg(constants::BMP5_REG_INT_STATUS).await?;
+            self.pending_interrupts = InterruptStatus::empty();
+            self.reset_detected = false;
+            Ok(())
+        }
+
+        /// Restore the configuration and power mode if the sensor reported an
+        /// unexpected reset, e.g. after a brown-out.
+        ///
+        /// Any status read can see the reset, so this runs before the driver
+        /// relies on the sensor configuration.
+        pub(crate) async fn recover_if_reset(&mut self) -> Result<(), Error<E>> {
+            if !self.reset_detected {
+                return Ok(());
+            }
+
+            self.reset_detected = false;
+            if let Err(err) = self.recover_from_reset().await {
+                // Try again next time
+                self.reset_detected = true;
+                return Err(err);
+            }
+            Ok(())
+        }
+
+        async fn recover_from_reset(&mut self) -> Result<(), Error<E>> {
+            let mode = self.requested_mode;
+            self.restore_config().await?;
+
+            match mode {
+                PowerMode::Normal => Ok(()),
+                // Restart the interrupted conversion
+                PowerMode::Forced => self.start_forced().await,
+                mode => self.write_power_mode(mode).await,
+            }
+        }
+
+        /// Re-apply the configuration after a reset, leaving the sensor in
+        /// normal mode.
+        async fn restore_config(&mut self) -> Result<(), Error<E>> {
+            // Over SPI the sensor is back in I2C mode
+            self.read_reg(constants::BMP5_REG_CHIP_ID).await?;
+            self.configure().await
+        }
+
+        async fn verify_chip_id(&mut self) -> Result<(), Error<E>> {
+            self.chip_info().await?;
+            Ok(())
+        }
+
+        /// Oversampling the sensor actually applies, which is lowered when the
+        /// requested one can't be sustained at the output data rate.
+        pub async fn effective_oversampling(&mut self) -> Result<OversamplingSettings, Error<E>> {
+            let osr_eff = self.read_reg(constants::BMP5_REG_OSR_EFF).await?;
+            Ok(OversamplingSettings::from_bits(osr_eff))
+        }
+
+        /// Identify the sensor variant and silicon revision.
+        pub async fn chip_info(&mut self) -> Result<ChipInfo, Error<E>> {
+            let mut buf = [0u8; 2];
+            self.read_regs(constants::BMP5_REG_CHIP_ID, &mut buf)
+                .await?;
+            let [chip_id, revision_id] = buf;
+
+            Ok(ChipInfo {
+                variant: ChipVariant::try_from(chip_id).map_err(Error::InvalidChipId)?,
+                chip_id,
+                revision_id,
+            })
+        }
+
+        async fn configure(&mut self) -> Result<(), Error<E>> {
+            // Enter standby mode
+            self.enter_standby().await?;
+
+            // Configure oversampling for pressure and temperature
+            self.write_osr_config().await?;
+
+            // Configure IIR low-pass filter and output data rate
+            self.write_iir_config().await?;
+            self.write_odr_config().await?;
+
+            // Configure FIFO
+            self.write_fifo_config().await?;
+
+            // Configure pressure out-of-range window
+            self.write_oor_config().await?;
+
+            // Configure the INT pin and enable interrupt sources
+            self.write_interrupt_pin_config().await?;
+
+            // Return to normal operation mode
+            self.write_power_mode(PowerMode::Normal).await?;
+
+            // Check if ODR and OSR settings are valid or we are
+            // operating in a degraded mode
+            self.check_effective_oversampling().await
+        }
+
+        /// Current configuration.
+        pub fn config(&self) -> &Config {
+            &self.config
+        }
+
+        /// Read the configuration back from the sensor registers.
+        ///
+        /// In deep standby the sensor runs with its deep standby ODR, IIR
+        /// filters and FIFO settings, which are reported as such. The timeout
+        /// isn't stored on the sensor and is taken from the current
+        /// configuration.
+        pub async fn read_config(&mut self) -> Result<Config, Error<E>> {
+            let osr_config = self.read_reg(constants::BMP5_REG_OSR_CONFIG).await?;
+            let dsp_iir = self.read_reg(constants::BMP5_REG_DSP_IIR).await?;
+            let odr_config = self.read_reg(constants::BMP5_REG_ODR_CONFIG).await?;
+            let fifo_config = self.read_reg(constants::BMP5_REG_FIFO_CONFIG).await?;
+            let fifo_sel = self.read_reg(constants::BMP5_REG_FIFO_SEL).await?;
+            let int_source = self.read_reg(constants::BMP5_REG_INT_SOURCE).await?;
+            let int_config = self.read_reg(constants::BMP5_REG_INT_CONFIG).await?;
+            let mut oor = [0u8; 4];
+            self.read_regs(constants::BMP5_REG_OOR_THR_P_LSB, &mut oor)
+                .await?;
+
+            // All codes of these fields are valid, decoding can't fail
+            let oversampling = OversamplingSettings::from_bits(osr_config);
+            let fifo = FifoConfig {
+                frame_selection: FifoFrameSelection::try_from(
+                    fifo_sel & constants::BMP5_FIFO_FRAME_SEL_MASK,
+                )
+                .unwrap_or_default(),
+                mode: if fifo_config & constants::BMP5_FIFO_MODE_STOP_ON_FULL != 0 {
+                    FifoMode::StopOnFull
+                } else {
+                    FifoMode::Streaming
+                },
+                decimation: FifoDecimation::try_from(
+                    (fifo_sel & constants::BMP5_FIFO_DEC_SEL_MASK)
+                        >> constants::BMP5_FIFO_DEC_SEL_POS,
+                )
+                .unwrap_or_default(),
+                threshold: fifo_config & constants::BMP5_FIFO_THRESHOLD_MASK,
+            };
+            let pressure_oor = OorConfig {
+                center: u32::from(oor[0])
+                    | (u32::from(oor[1]) << 8)
+                    | (u32::from(oor[3] & constants::BMP5_OOR_THR_P_XMSB_MASK) << 16),
+                range: oor[2],
+                count_limit: OorCountLimit::try_from(
+                    (oor[3] & constants::BMP5_OOR_COUNT_LIMIT_MASK)
+                        >> constants::BMP5_OOR_COUNT_LIMIT_POS,
+                )
+                .unwrap_or_default(),
+            };
+
+            Ok(Config {
+                temperature_oversampling: oversampling.temperature,
+                temperature_iir_filter: IIRFilter::try_from(
+                    dsp_iir & constants::BMP5_IIR_FILTER_TEMP_MASK,
+                )
+                .unwrap_or_default(),
+                pressure_oversampling: oversampling.pressure,
+                pressure_iir_filter: IIRFilter::try_from(
+                    (dsp_iir & constants::BMP5_IIR_FILTER_PRESS_MASK)
+                        >> constants::BMP5_IIR_FILTER_PRESS_POS,
+                )
+                .unwrap_or_default(),
+                output_data_rate: OutputDataRate::try_from(
+                    (odr_config & constants::BMP5_ODR_MASK) >> constants::BMP5_ODR_POS,
+                )
+                .unwrap_or_default(),
+                fifo,
+                interrupt_source: InterruptSource::from_bits_truncate(int_source),
+                interrupt_pin: int_config.into(),
+                pressure_oor,
+                timeout_us: self.config.timeout_us,
+            })
+        }
+
+        /// Apply a new configuration without resetting the sensor.
+        ///
+        /// Only the registers that differ from the current configuration are
+        /// written. If oversampling, IIR filters, output data rate, FIFO or
+        /// out-of-range window change, the sensor is briefly put into standby,
+        /// which flushes the FIFO. Values that don't fit their registers, see
+        /// [`Config::validate`], are rejected with [`Error::InvalidConfig`]
+        /// before anything is written. As in [`init`](Self::init), the sensor
+        /// decides whether the output data rate can be sustained, which is
+        /// checked once the sensor is back in its power mode. If the sensor
+        /// rejects the configuration the previous one is restored.
+        pub async fn set_config(&mut self, config: Config) -> Result<(), Error<E>> {
+            config
+                .validate_registers()
+                .map_err(|_| Error::InvalidConfig)?;
+            // The deltas below assume the sensor still holds the current
+            // configuration
+            self.recover_if_reset().await?;
+
+            let previous = self.config;
+            if !Self::requires_standby(&previous, &config) {
+                return self.apply_config_or_rollback(previous, config).await;
+            }
+
+            // The mode is captured once, so a rollback ends up in it as well
+            let mode = self.suspend_for_update().await?;
+            let result = self.apply_config_or_rollback(previous, config).await;
+            self.resume_after_update(mode, result).await?;
+
+            // Checked once the sensor runs in its mode again rather than in
+            // standby, where it doesn't convert at the output data rate
+            let rate_changed = previous.oversampling() != config.oversampling()
+                || previous.output_data_rate != config.output_data_rate;
+            if rate_changed && let Err(err) = self.check_effective_oversampling().await {
+                self.rollback_config(config, previous).await;
+                return Err(err);
+            }
+            Ok(())
+        }
+
+        /// Change the temperature and pressure oversampling.
+        pub async fn set_oversampling(
+            &mut self,
+            oversampling: OversamplingSettings,
+        ) -> Result<(), Error<E>> {
+            self.set_config(Config {
+                temperature_oversampling: oversampling.temperature,
+                pressure_oversampling: oversampling.pressure,
+                ..self.config
+            })
+            .await
+        }
+
+        /// Change the temperature and pressure IIR low-pass filters.
+        pub async fn set_iir_filter(
+            &mut self,
+            temperature: IIRFilter,
+            pressure: IIRFilter,
+        ) -> Result<(), Error<E>> {
+            self.set_config(Config {
+                temperature_iir_filter: temperature,
+                pressure_iir_filter: pressure,
+                ..self.config
+            })
+            .await
+        }
+
+        /// Change the output data rate.
+        pub async fn set_output_data_rate(
+            &mut self,
+            output_data_rate: OutputDataRate,
+        ) -> Result<(), Error<E>> {
+            self.set_config(Config {
+                output_data_rate,
+                ..self.config
+            })
+            .await
+        }
+
+        /// Whether changing from `from` to `to` writes registers that are
+        /// only writable in standby.
+        fn requires_standby(from: &Config, to: &Config) -> bool {
+            from.oversampling() != to.oversampling()
+                || from.temperature_iir_filter != to.temperature_iir_filter
+                || from.pressure_iir_filter != to.pressure_iir_filter
+                || from.output_data_rate != to.output_data_rate
+                || from.fifo != to.fifo
+                || from.pressure_oor != to.pressure_oor
+        }
+
+        async fn apply_config_or_rollback(
+            &mut self,
+            previous: Config,
+            config: Config,
+        ) -> Result<(), Error<E>> {
+            if let Err(err) = self.apply_config(previous, config).await {
+                // Same delta, writing the previous values back. The original
+                // error is reported even if that fails as well.
+                let _ = self.apply_config(config, previous).await;
+                return Err(err);
+            }
+            Ok(())
+        }
+
+        /// Restore `previous` after the sensor rejected `config`, which
+        /// changed registers only writable in standby. Errors are dropped in
+        /// favour of the one that caused the rollback.
+        async fn rollback_config(&mut self, config: Config, previous: Config) {
+            if let Ok(mode) = self.suspend_for_update().await {
+                let result = self.apply_config(config, previous).await;
+                let _ = self.resume_after_update(mode, result).await;
+            }
+        }
+
+        /// Write the registers that differ between `from` and `to`, the
+        /// sensor needs to be in standby if
+        /// [`requires_standby`](Self::requires_standby).
+        async fn apply_config(&mut self, from: Config, to: Config) -> Result<(), Error<E>> {
+            let osr_changed = from.oversampling() != to.oversampling();
+            let iir_changed = from.temperature_iir_filter != to.temperature_iir_filter
+                || from.pressure_iir_filter != to.pressure_iir_filter;
+            let odr_changed = from.output_data_rate != to.output_data_rate;
+            let fifo_changed = from.fifo != to.fifo;
+            let oor_changed = from.pressure_oor != to.pressure_oor;
+            let interrupt_pin_changed = from.interrupt_pin != to.interrupt_pin;
+            let interrupt_source_changed = from.interrupt_source != to.interrupt_source;
+
+            self.config = to;
+
+            if osr_changed {
+                self.write_osr_config().await?;
+            }
+            if iir_changed {
+                self.write_iir_config().await?;
+            }
+            if odr_changed {
+                self.write_odr_config().await?;
+            }
+            if fifo_changed {
+                self.write_fifo_config().await?;
+            }
+            if oor_changed {
+                self.write_oor_config().await?;
+            }
+            if interrupt_pin_changed {
+                // Also restores the interrupt sources
+                self.write_interrupt_pin_config().await?;
+            } else if interrupt_source_changed {
+                self.write_interrupt_source().await?;
+            }
+
+            Ok(())
+        }
+
+        async fn check_effective_oversampling(&mut self) -> Result<(), Error<E>> {
+            let osr_eff = self.read_reg(constants::BMP5_REG_OSR_EFF).await?;
+            if osr_eff & constants::BMP5_OSR_EFF_VALID_ODR == 0 {
+                return Err(Error::DegradedOversampling {
+                    requested: self.config.oversampling(),
+                    effective: OversamplingSettings::from_bits(osr_eff),
+                });
+            }
+            Ok(())
+        }
+
+        async fn write_osr_config(&mut self) -> Result<(), Error<E>> {
+            let osr_config: u8 = constants::BMP5_OSR_PRESS_EN
+                | (u8::from(self.config.pressure_oversampling) << constants::BMP5_OSR_PRESS_POS)
+                | u8::from(self.config.temperature_oversampling);
+
+            self.update_reg(
+                constants::BMP5_REG_OSR_CONFIG,
+                constants::BMP5_OSR_MASK | constants::BMP5_OSR_PRESS_EN,
+                osr_config,
+            )
+            .await
+        }
+
+        async fn enter_standby(&mut self) -> Result<(), Error<E>> {
+            let odr_config = self.read_reg(constants::BMP5_REG_ODR_CONFIG).await?;
+            let standby = (odr_config & !constants::BMP5_MODE_MASK)
+                | constants::BMP5_ODR_DEEPSLEEP_DIS
+                | constants::BMP5_MODE_STANDBY;
+            self.write_reg(constants::BMP5_REG_ODR_CONFIG, standby)
+                .await?;
+
+            // Give any conversion in progress time to complete
+            if odr_config & constants::BMP5_MODE_MASK != constants::BMP5_MODE_STANDBY {
+                self.delay.delay_us(2500).await;
+            }
+            Ok(())
+        }
+
+        async fn write_iir_config(&mut self) -> Result<(), Error<E>> {
+            let iir_config: u8 = (u8::from(self.config.pressure_iir_filter)
+                << constants::BMP5_IIR_FILTER_PRESS_POS)
+                | u8::from(self.config.temperature_iir_filter);
+            self.update_reg(
+                constants::BMP5_REG_DSP_IIR,
+                constants::BMP5_IIR_FILTER_MASK,
+                iir_config,
+            )
+            .await
+        }
+
+        async fn write_odr_config(&mut self) -> Result<(), Error<E>> {
+            self.update_reg(
+                constants::BMP5_REG_ODR_CONFIG,
+                constants::BMP5_ODR_MASK,
+                u8::from(self.config.output_data_rate) << constants::BMP5_ODR_POS,
+            )
+            .await
+        }
+
+        async fn read_measurement(&mut self) -> Result<Measurement, Error<E>> {
+            let mut buf = [0u8; 6];
+            self.read_regs(constants::BMP5_REG_TEMP_DATA_XLSB, &mut buf)
+                .await?;
+
+            Ok(Measurement::from_bytes(&buf))
+        }
+
+        /// Wait until any of the interrupt status flags in `mask` is set, for
+        /// at most `timeout_us` if given.
+        ///
+        /// The flags in `mask` are acknowledged, others are kept pending for
+        /// `read_interrupt_status`. If the sensor reports an unexpected reset
+        /// meanwhile, its configuration is restored and the wait goes on.
+        pub(crate) async fn wait_for_interrupt(
+            &mut self,
+            mask: InterruptStatus,
+            poll_interval_us: u32,
+            timeout_us: Option<u32>,
+        ) -> Result<InterruptStatus, Error<E>> {
+            let mut remaining_us = timeout_us;
+            loop {
+                self.fetch_interrupt_status().await?;
+                if !mask.contains(InterruptStatus::POR_SOFTRESET_COMPLETE) {
+                    self.recover_if_reset().await?;
+                }
+
+                let int_status = self.pending_interrupts & mask;
+                if !int_status.is_empty() {
+                    self.pending_interrupts.remove(mask);
+                    return Ok(int_status);
+                }
+
+                if remaining_us == Some(0) {
+                    return Err(Error::Timeout);
+                }
+
+                // Don't rely on the pin alone, a reset also clears INT_CONFIG
+                // and the pin would never be asserted again.
+                let max_wait_us =
+                    self.config.output_data_rate.period_us() * constants::BMP5_TIMEOUT_PERIODS;
+                let wait_us = remaining_us.map_or(max_wait_us, |t| t.min(max_wait_us));
+                let waited_us = self.wait_for_pin(poll_interval_us, wait_us).await?;
+                if let Some(remaining_us) = remaining_us.as_mut() {
+                    *remaining_us = remaining_us.saturating_sub(waited_us);
+                }
+            }
+        }
+
+        /// Wait for the INT pin to be asserted for at most `timeout_us`, or for
+        /// `poll_interval_us` without a usable pin, returning the time
+        /// accounted for the wait.
+        ///
+        /// The time spent waiting on the pin can't be measured, each wake-up
+        /// counts as `poll_interval_us`. In pulsed mode a pulse raised between
+        /// the status read and the wait is missed, latched mode doesn't have
+        /// this issue.
+        #[maybe_async_cfg::only_if(async)]
+        async fn wait_for_pin(
+            &mut self,
+            poll_interval_us: u32,
+            timeout_us: u32,
+        ) -> Result<u32, Error<E>> {
+            use crate::{InterruptPinMode, InterruptPinPolarity};
+            use embedded_hal::digital::Error as _;
+
+            let interrupt_pin = self.config.interrupt_pin;
+            let pin = match self.int_pin.as_mut() {
+                Some(pin) if interrupt_pin.enabled => pin,
+                _ => {
+                    let delay_us = poll_interval_us.min(timeout_us);
+                    self.delay.delay_us(delay_us).await;
+                    return Ok(delay_us);
+                }
+            };
+
+            let asserted = async {
+                match (interrupt_pin.mode, interrupt_pin.polarity) {
+                    (InterruptPinMode::Latched, InterruptPinPolarity::ActiveHigh) => {
+                        pin.wait_for_high().await
+                    }
+                    (InterruptPinMode::Latched, InterruptPinPolarity::ActiveLow) => {
+                        pin.wait_for_low().await
+                    }
+                    (InterruptPinMode::Pulsed, InterruptPinPolarity::ActiveHigh) => {
+                        pin.wait_for_rising_edge().await
+                    }
+                    (InterruptPinMode::Pulsed, InterruptPinPolarity::ActiveLow) => {
+                        pin.wait_for_falling_edge().await
+                    }
+                }
+            };
+
+            match with_timeout(asserted, self.delay.delay_us(timeout_us)).await {
+                Some(result) => {
+                    result.map_err(|err| Error::Pin(err.kind()))?;
+                    Ok(poll_interval_us.min(timeout_us))
+                }
+                None => Ok(timeout_us),
+            }
+        }
+
+        /// Wait for the INT pin to be asserted for at most `timeout_us`,
+        /// polling its level, or for `poll_interval_us` without a usable pin,
+        /// returning the time spent.
+        ///
+        /// Pulses are too short to be caught by polling, so in pulsed mode
+        /// the status register is polled instead.
+        #[maybe_async_cfg::only_if(sync)]
+        async fn wait_for_pin(
+            &mut self,
+            poll_interval_us: u32,
+            timeout_us: u32,
+        ) -> Result<u32, Error<E>> {
+            use crate::{InterruptPinMode, InterruptPinPolarity};
+            use embedded_hal::digital::Error as _;
+
+            let interrupt_pin = self.config.interrupt_pin;
+            let delay_us = poll_interval_us.min(timeout_us);
+            let pin = match self.int_pin.as_mut() {
+                Some(pin)
+                    if interrupt_pin.enabled && interrupt_pin.mode == InterruptPinMode::Latched =>
+                {
+                    pin
+                }
+                _ => {
+                    self.delay.delay_us(delay_us).await;
+                    return Ok(delay_us);
+                }
+            };
+
+            let mut waited_us = 0;
+            loop {
+                let asserted = match interrupt_pin.polarity {
+                    InterruptPinPolarity::ActiveHigh => pin.is_high(),
+                    InterruptPinPolarity::ActiveLow => pin.is_low(),
+                }
+                .map_err(|err| Error::Pin(err.kind()))?;
+                if asserted || waited_us >= timeout_us {
+                    return Ok(waited_us);
+                }
+                self.delay.delay_us(delay_us).await;
+                waited_us += delay_us;
+            }
+        }
+
+        pub(crate) async fn update_reg(
+            &mut self,
+            reg: u8,
+            mask: u8,
+            value: u8,
+        ) -> Result<(), Error<E>> {
+            let current = self.read_reg(reg).await?;
+            let new = (current & !mask) | (value & mask);
+            self.write_reg(reg, new).await
+        }
+
+        pub(crate) async fn read_reg(&mut self, reg: u8) -> Result<u8, Error<E>> {
+            self.interface.read_register(reg).await.map_err(Error::Bus)
+        }
+
+        pub(crate) async fn read_regs(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Error<E>> {
+            self.interface
+                .read_registers(reg, buf)
+                .await
+                .map_err(Error::Bus)
+        }
+
+        pub(crate) async fn write_reg(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+            self.interface
+                .write_register(reg, value)
+                .await
+                .map_err(Error::Bus)
+        }
+
+        pub(crate) async fn write_regs(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<E>> {
+            self.interface
+                .write_registers(reg, data)
+                .await
+                .map_err(Error::Bus)
+        }
+    }
+
+    /// Run `future`, or give up once `timeout` completes.
+    #[maybe_async_cfg::only_if(async)]
+    async fn with_timeout<F: Future, T: Future>(future: F, timeout: T) -> Option<F::Output> {
+        use core::task::Poll;
+
+        let mut future = core::pin::pin!(future);
+        let mut timeout = core::pin::pin!(timeout);
+        core::future::poll_fn(|cx| {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                return Poll::Ready(Some(output));
+            }
+            timeout.as_mut().poll(cx).map(|_| None)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fifo::{FifoConfig, FifoFrameSelection};
+    use crate::mock::Mock;
+    use crate::oor::OorConfig;
+    use crate::{
+        Config, Error, IIRFilter, InterruptSource, InterruptStatus, OutputDataRate, Oversampling,
+        PowerMode, constants,
+    };
+
+    fn config() -> Config {
+        Config {
+            pressure_oversampling: Oversampling::Oversampling8X,
+            pressure_iir_filter: IIRFilter::Coeff3,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn measure_forced_recovers_from_reset() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        sensor.set_power_mode(PowerMode::Standby).unwrap();
+        let osr_config = mock.reg(constants::BMP5_REG_OSR_CONFIG);
+
+        // Brown-out between two measurements
+        mock.0.borrow_mut().reset();
+
+        let measurement = sensor.measure_forced().unwrap();
+        assert_eq!(measurement.pressure, 101325.0);
+        assert_eq!(mock.reg(constants::BMP5_REG_OSR_CONFIG), osr_config);
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_INT_SOURCE),
+            constants::BMP5_INT_ENABLE_DRDY
+        );
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::Standby);
+    }
+
+    #[test]
+    fn read_interrupt_status_reports_reset() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        let dsp_iir = mock.reg(constants::BMP5_REG_DSP_IIR);
+
+        mock.0.borrow_mut().reset();
+
+        let status = sensor.read_interrupt_status().unwrap();
+        assert!(status.contains(InterruptStatus::POR_SOFTRESET_COMPLETE));
+        assert_eq!(mock.reg(constants::BMP5_REG_DSP_IIR), dsp_iir);
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::Normal);
+        sensor.measure().unwrap();
+    }
+
+    #[test]
+    fn set_config_rejects_out_of_range_registers() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        mock.writes();
+
+        let result = sensor.set_config(Config {
+            pressure_oor: OorConfig {
+                center: 0x20000,
+                ..OorConfig::default()
+            },
+            ..config()
+        });
+        assert!(matches!(result, Err(Error::InvalidConfig)));
+        assert_eq!(mock.writes(), []);
+        assert_eq!(*sensor.config(), config());
+    }
+
+    #[test]
+    fn set_config_leaves_the_rate_to_the_sensor() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+
+        // Beyond the conversion time model, but accepted by the sensor
+        let fast = Config {
+            output_data_rate: OutputDataRate::OutputDataRate240Hz,
+            ..config()
+        };
+        assert!(fast.validate().is_err());
+        sensor.set_config(fast).unwrap();
+        assert_eq!(*sensor.config(), fast);
+    }
+
+    #[test]
+    fn set_config_rolls_back_an_unsustainable_rate() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        let odr_config = mock.reg(constants::BMP5_REG_ODR_CONFIG);
+        mock.0.borrow_mut().odr_valid = false;
+
+        let result = sensor.set_output_data_rate(OutputDataRate::OutputDataRate240Hz);
+        assert!(matches!(
+            result,
+            Err(Error::DegradedOversampling { requested, .. }) if requested == config().oversampling()
+        ));
+        assert_eq!(*sensor.config(), config());
+        assert_eq!(mock.reg(constants::BMP5_REG_ODR_CONFIG), odr_config);
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::Normal);
+    }
+
+    #[test]
+    fn set_config_keeps_the_error_when_the_rollback_fails() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        {
+            let mut registers = mock.0.borrow_mut();
+            registers.odr_valid = false;
+            // Enough for standby, the new rate and normal mode, not for the
+            // rollback
+            registers.fail_writes_to = Some((constants::BMP5_REG_ODR_CONFIG, 3));
+        }
+
+        let result = sensor.set_output_data_rate(OutputDataRate::OutputDataRate240Hz);
+        assert!(matches!(result, Err(Error::DegradedOversampling { .. })));
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_ODR_CONFIG) & constants::BMP5_ODR_MASK,
+            u8::from(OutputDataRate::OutputDataRate240Hz) << constants::BMP5_ODR_POS
+        );
+    }
+
+    #[test]
+    fn set_config_writes_only_the_changes() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        mock.writes();
+
+        sensor.set_config(config()).unwrap();
+        assert_eq!(mock.writes(), []);
+
+        // No standby needed for the interrupt sources
+        sensor
+            .enable_interrupts(InterruptSource::PRESSURE_OUT_OF_RANGE)
+            .unwrap();
+        assert_eq!(
+            mock.writes(),
+            [(
+                constants::BMP5_REG_INT_SOURCE,
+                constants::BMP5_INT_ENABLE_DRDY | constants::BMP5_INT_ENABLE_PRESSURE_OOR
+            )]
+        );
+
+        sensor
+            .set_iir_filter(IIRFilter::Bypass, IIRFilter::Coeff7)
+            .unwrap();
+        let registers: Vec<u8> = mock.writes().into_iter().map(|(reg, _)| reg).collect();
+        assert_eq!(
+            registers,
+            [
+                constants::BMP5_REG_ODR_CONFIG,
+                constants::BMP5_REG_DSP_IIR,
+                constants::BMP5_REG_ODR_CONFIG
+            ]
+        );
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_DSP_IIR),
+            constants::BMP5_IIR_FILTER_COEFF_7 << constants::BMP5_IIR_FILTER_PRESS_POS
+        );
+    }
+
+    #[test]
+    fn set_config_rolls_back_on_bus_errors() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(config());
+        sensor.init().unwrap();
+        let dsp_iir = mock.reg(constants::BMP5_REG_DSP_IIR);
+        mock.0.borrow_mut().fail_writes_to = Some((constants::BMP5_REG_OOR_THR_P_LSB, 0));
+
+        // The filters are written before the out-of-range window
+        let result = sensor.set_config(Config {
+            pressure_iir_filter: IIRFilter::Coeff127,
+            pressure_oor: OorConfig {
+                center: 90000,
+                ..OorConfig::default()
+            },
+            ..config()
+        });
+        assert!(matches!(result, Err(Error::Bus(_))));
+        assert_eq!(*sensor.config(), config());
+        assert_eq!(mock.reg(constants::BMP5_REG_DSP_IIR), dsp_iir);
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::Normal);
+    }
+
+    #[test]
+    fn deep_standby_restores_the_configuration() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(Config {
+            fifo: FifoConfig {
+                frame_selection: FifoFrameSelection::PressureAndTemperature,
+                ..FifoConfig::default()
+            },
+            ..config()
+        });
+        sensor.init().unwrap();
+        let odr_config = mock.reg(constants::BMP5_REG_ODR_CONFIG);
+        let dsp_iir = mock.reg(constants::BMP5_REG_DSP_IIR);
+        let fifo_sel = mock.reg(constants::BMP5_REG_FIFO_SEL);
+
+        sensor.set_power_mode(PowerMode::DeepStandby).unwrap();
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::DeepStandby);
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_ODR_CONFIG),
+            constants::BMP5_ODR_01_HZ << constants::BMP5_ODR_POS
+        );
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_DSP_IIR) & constants::BMP5_IIR_FILTER_MASK,
+            0
+        );
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_FIFO_SEL) & constants::BMP5_FIFO_FRAME_SEL_MASK,
+            0
+        );
+
+        sensor.set_power_mode(PowerMode::Normal).unwrap();
+        assert_eq!(mock.reg(constants::BMP5_REG_ODR_CONFIG), odr_config);
+        assert_eq!(mock.reg(constants::BMP5_REG_DSP_IIR), dsp_iir);
+        assert_eq!(mock.reg(constants::BMP5_REG_FIFO_SEL), fifo_sel);
+        sensor.measure().unwrap();
+    }
+
+    #[test]
+    fn standby_keeps_deep_standby_disabled() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(Config {
+            pressure_iir_filter: IIRFilter::Bypass,
+            output_data_rate: OutputDataRate::OutputDataRate1Hz,
+            ..config()
+        });
+        sensor.init().unwrap();
+
+        sensor.set_power_mode(PowerMode::Standby).unwrap();
+        assert_eq!(sensor.power_mode().unwrap(), PowerMode::Standby);
+        assert_ne!(
+            mock.reg(constants::BMP5_REG_ODR_CONFIG) & constants::BMP5_ODR_DEEPSLEEP_DIS,
+            0
+        );
+    }
+}
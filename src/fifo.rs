@@ -0,0 +1,506 @@
+use crate::{
+    Error, InterruptSource, Measurement, constants, driver, interface, pressure_from_bytes,
+    temperature_from_bytes,
+};
+
+/// Maximum number of frames the FIFO can hold
+pub const FIFO_CAPACITY: usize = 32;
+
+/// Data stored in each FIFO frame
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoFrameSelection {
+    /// FIFO disabled
+    #[default]
+    Disabled,
+    /// Temperature only, 3 bytes per frame
+    Temperature,
+    /// Pressure only, 3 bytes per frame
+    Pressure,
+    /// Pressure and temperature, 6 bytes per frame
+    PressureAndTemperature,
+}
+
+impl FifoFrameSelection {
+    /// Size of a single frame in bytes
+    pub fn frame_size(self) -> usize {
+        match self {
+            FifoFrameSelection::Disabled => 0,
+            FifoFrameSelection::Temperature | FifoFrameSelection::Pressure => 3,
+            FifoFrameSelection::PressureAndTemperature => 6,
+        }
+    }
+
+    /// Number of frames the FIFO can hold
+    pub fn capacity(self) -> usize {
+        match self {
+            FifoFrameSelection::Disabled => 0,
+            FifoFrameSelection::Temperature | FifoFrameSelection::Pressure => FIFO_CAPACITY,
+            FifoFrameSelection::PressureAndTemperature => FIFO_CAPACITY / 2,
+        }
+    }
+}
+
+impl From<FifoFrameSelection> for u8 {
+    fn from(frame_selection: FifoFrameSelection) -> u8 {
+        match frame_selection {
+            FifoFrameSelection::Disabled => constants::BMP5_FIFO_FRAME_SEL_DISABLED,
+            FifoFrameSelection::Temperature => constants::BMP5_FIFO_FRAME_SEL_TEMP,
+            FifoFrameSelection::Pressure => constants::BMP5_FIFO_FRAME_SEL_PRESS,
+            FifoFrameSelection::PressureAndTemperature => constants::BMP5_FIFO_FRAME_SEL_PRESS_TEMP,
+        }
+    }
+}
+
+impl TryFrom<u8> for FifoFrameSelection {
+    type Error = u8;
+
+    /// Frame selection for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            constants::BMP5_FIFO_FRAME_SEL_DISABLED => Ok(FifoFrameSelection::Disabled),
+            constants::BMP5_FIFO_FRAME_SEL_TEMP => Ok(FifoFrameSelection::Temperature),
+            constants::BMP5_FIFO_FRAME_SEL_PRESS => Ok(FifoFrameSelection::Pressure),
+            constants::BMP5_FIFO_FRAME_SEL_PRESS_TEMP => {
+                Ok(FifoFrameSelection::PressureAndTemperature)
+            }
+            code => Err(code),
+        }
+    }
+}
+
+/// FIFO behaviour once all frames are in use
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoMode {
+    /// Oldest frames are overwritten
+    #[default]
+    Streaming,
+    /// New frames are discarded
+    StopOnFull,
+}
+
+/// FIFO decimation, only every Nth sample is stored
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoDecimation {
+    /// Every sample
+    #[default]
+    Decimation1X,
+    /// Every 2nd sample
+    Decimation2X,
+    /// Every 4th sample
+    Decimation4X,
+    /// Every 8th sample
+    Decimation8X,
+    /// Every 16th sample
+    Decimation16X,
+    /// Every 32nd sample
+    Decimation32X,
+    /// Every 64th sample
+    Decimation64X,
+    /// Every 128th sample
+    Decimation128X,
+}
+
+impl From<FifoDecimation> for u8 {
+    fn from(decimation: FifoDecimation) -> u8 {
+        match decimation {
+            FifoDecimation::Decimation1X => 0,
+            FifoDecimation::Decimation2X => 1,
+            FifoDecimation::Decimation4X => 2,
+            FifoDecimation::Decimation8X => 3,
+            FifoDecimation::Decimation16X => 4,
+            FifoDecimation::Decimation32X => 5,
+            FifoDecimation::Decimation64X => 6,
+            FifoDecimation::Decimation128X => 7,
+        }
+    }
+}
+
+impl TryFrom<u8> for FifoDecimation {
+    type Error = u8;
+
+    /// Decimation for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            0 => Ok(FifoDecimation::Decimation1X),
+            1 => Ok(FifoDecimation::Decimation2X),
+            2 => Ok(FifoDecimation::Decimation4X),
+            3 => Ok(FifoDecimation::Decimation8X),
+            4 => Ok(FifoDecimation::Decimation16X),
+            5 => Ok(FifoDecimation::Decimation32X),
+            6 => Ok(FifoDecimation::Decimation64X),
+            7 => Ok(FifoDecimation::Decimation128X),
+            code => Err(code),
+        }
+    }
+}
+
+/// FIFO configuration
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FifoConfig {
+    /// Data stored in each frame
+    pub frame_selection: FifoFrameSelection,
+    /// Behaviour when full
+    pub mode: FifoMode,
+    /// Decimation
+    pub decimation: FifoDecimation,
+    /// Number of frames that triggers the FIFO threshold interrupt, up to 15
+    /// with pressure and temperature frames or 31 otherwise. 0 disables it.
+    pub threshold: u8,
+}
+
+impl FifoConfig {
+    pub(crate) const fn max_threshold(&self) -> u8 {
+        match self.frame_selection {
+            FifoFrameSelection::PressureAndTemperature => {
+                constants::BMP5_FIFO_MAX_THRESHOLD_PRESS_TEMP
+            }
+            _ => constants::BMP5_FIFO_MAX_THRESHOLD,
+        }
+    }
+}
+
+/// A decoded FIFO frame
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoFrame {
+    /// Temperature in degrees Celsius
+    Temperature(f32),
+    /// Pressure in Pascals
+    Pressure(f32),
+    /// Pressure and temperature
+    PressureAndTemperature(Measurement),
+}
+
+impl FifoFrame {
+    /// Temperature in degrees Celsius, if present in the frame
+    pub fn temperature(&self) -> Option<f32> {
+        match self {
+            FifoFrame::Temperature(temperature) => Some(*temperature),
+            FifoFrame::Pressure(_) => None,
+            FifoFrame::PressureAndTemperature(measurement) => Some(measurement.temperature),
+        }
+    }
+
+    /// Pressure in Pascals, if present in the frame
+    pub fn pressure(&self) -> Option<f32> {
+        match self {
+            FifoFrame::Temperature(_) => None,
+            FifoFrame::Pressure(pressure) => Some(*pressure),
+            FifoFrame::PressureAndTemperature(measurement) => Some(measurement.pressure),
+        }
+    }
+}
+
+impl From<FifoFrame> for Measurement {
+    /// Values not present in the frame are reported as NaN.
+    fn from(frame: FifoFrame) -> Measurement {
+        Measurement {
+            temperature: frame.temperature().unwrap_or(f32::NAN),
+            pressure: frame.pressure().unwrap_or(f32::NAN),
+        }
+    }
+}
+
+/// Parser for raw FIFO data
+///
+/// Decodes a buffer read from the FIFO data register into frames, skipping
+/// empty frames. It doesn't need access to the sensor, so it can also be used
+/// on captured or recorded FIFO dumps. A trailing partial frame is ignored.
+pub struct FifoParser<'a> {
+    frames: core::slice::ChunksExact<'a, u8>,
+    frame_selection: FifoFrameSelection,
+}
+
+impl<'a> FifoParser<'a> {
+    /// Parse `data` as frames of the given selection.
+    pub fn new(data: &'a [u8], frame_selection: FifoFrameSelection) -> Self {
+        // A disabled FIFO doesn't produce any frames.
+        let data = match frame_selection {
+            FifoFrameSelection::Disabled => &[],
+            _ => data,
+        };
+
+        Self {
+            frames: data.chunks_exact(frame_selection.frame_size().max(1)),
+            frame_selection,
+        }
+    }
+}
+
+impl Iterator for FifoParser<'_> {
+    type Item = FifoFrame;
+
+    fn next(&mut self) -> Option<FifoFrame> {
+        loop {
+            let frame = self.frames.next()?;
+            if frame[..3].iter().all(|&b| b == constants::BMP5_FIFO_EMPTY) {
+                continue;
+            }
+
+            return match self.frame_selection {
+                FifoFrameSelection::Disabled => None,
+                FifoFrameSelection::Temperature => {
+                    Some(FifoFrame::Temperature(temperature_from_bytes(frame)))
+                }
+                FifoFrameSelection::Pressure => {
+                    Some(FifoFrame::Pressure(pressure_from_bytes(frame)))
+                }
+                FifoFrameSelection::PressureAndTemperature => Some(
+                    FifoFrame::PressureAndTemperature(Measurement::from_bytes(frame)),
+                ),
+            };
+        }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(
+            asynch(sync = "blocking"),
+            embedded_hal_async(sync = "embedded_hal"),
+            Wait(sync = "InputPin")
+        )
+    ),
+    async(keep_self)
+)]
+impl<I, D, P, E> driver::asynch::Bmp5<I, D, P>
+where
+    I: interface::asynch::RegisterInterface<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    P: embedded_hal_async::digital::Wait,
+{
+    /// Configure the FIFO.
+    ///
+    /// The sensor is briefly put into standby while the FIFO is reconfigured,
+    /// which also flushes it.
+    pub async fn set_fifo_config(&mut self, fifo: FifoConfig) -> Result<(), Error<E>> {
+        // Reject before the FIFO gets flushed
+        if fifo.threshold > fifo.max_threshold() {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mode = self.suspend_for_update().await?;
+        self.config.fifo = fifo;
+        let result = self.write_fifo_config().await;
+        self.resume_after_update(mode, result).await
+    }
+
+    /// Set the number of frames that triggers the FIFO threshold interrupt.
+    ///
+    /// Unlike [`set_fifo_config`](Self::set_fifo_config) this doesn't flush
+    /// the FIFO.
+    pub async fn set_fifo_threshold(&mut self, threshold: u8) -> Result<(), Error<E>> {
+        let fifo = FifoConfig {
+            threshold,
+            ..self.config.fifo
+        };
+        if threshold > fifo.max_threshold() {
+            return Err(Error::InvalidConfig);
+        }
+
+        self.config.fifo = fifo;
+        self.update_reg(
+            constants::BMP5_REG_FIFO_CONFIG,
+            constants::BMP5_FIFO_THRESHOLD_MASK,
+            threshold,
+        )
+        .await
+    }
+
+    /// Wait for the FIFO threshold or full interrupt, then drain the FIFO into
+    /// `measurements`.
+    ///
+    /// At least one of [`InterruptSource::FIFO_THRESHOLD`] and
+    /// [`InterruptSource::FIFO_FULL`] needs to be enabled. Returns the number
+    /// of measurements written, as [`read_fifo`](Self::read_fifo).
+    pub async fn wait_fifo_ready(
+        &mut self,
+        measurements: &mut [Measurement],
+    ) -> Result<usize, Error<E>> {
+        let sources = self.config.interrupt_source
+            & (InterruptSource::FIFO_THRESHOLD | InterruptSource::FIFO_FULL);
+        if sources.is_empty() {
+            return Err(Error::InvalidConfig);
+        }
+
+        // A new frame can't arrive faster than once per ODR period
+        self.wait_for_interrupt(
+            sources.into(),
+            self.config.output_data_rate.period_us(),
+            self.config.timeout_us,
+        )
+        .await?;
+
+        self.read_fifo(measurements).await
+    }
+
+    /// Number of frames currently stored in the FIFO.
+    pub async fn fifo_count(&mut self) -> Result<usize, Error<E>> {
+        let count = self.read_reg(constants::BMP5_REG_FIFO_COUNT).await?;
+        Ok((count & constants::BMP5_FIFO_COUNT_MASK) as usize)
+    }
+
+    /// Drain the FIFO into `measurements` in a single burst read.
+    ///
+    /// Returns the number of measurements written, frames that don't fit
+    /// are left in the FIFO. With a single-channel frame selection the
+    /// missing value is reported as NaN.
+    pub async fn read_fifo(&mut self, measurements: &mut [Measurement]) -> Result<usize, Error<E>> {
+        let frame_selection = self.config.fifo.frame_selection;
+        let frame_size = frame_selection.frame_size();
+        if frame_size == 0 {
+            return Ok(0);
+        }
+
+        // Don't trust the count beyond what the FIFO can actually hold
+        let frames = self
+            .fifo_count()
+            .await?
+            .min(frame_selection.capacity())
+            .min(measurements.len());
+        if frames == 0 {
+            return Ok(0);
+        }
+
+        // Both 3 and 6 byte frames fill the same 96 bytes
+        let mut buf = [0u8; FIFO_CAPACITY * 3];
+        let buf = &mut buf[..frames * frame_size];
+        self.read_regs(constants::BMP5_REG_FIFO_DATA, buf).await?;
+
+        let mut count = 0;
+        for frame in FifoParser::new(buf, frame_selection) {
+            measurements[count] = frame.into();
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub(crate) async fn write_fifo_config(&mut self) -> Result<(), Error<E>> {
+        let fifo = self.config.fifo;
+        if fifo.threshold > fifo.max_threshold() {
+            return Err(Error::InvalidConfig);
+        }
+
+        let fifo_config = match fifo.mode {
+            FifoMode::Streaming => 0,
+            FifoMode::StopOnFull => constants::BMP5_FIFO_MODE_STOP_ON_FULL,
+        } | fifo.threshold;
+        self.update_reg(
+            constants::BMP5_REG_FIFO_CONFIG,
+            constants::BMP5_FIFO_MODE_STOP_ON_FULL | constants::BMP5_FIFO_THRESHOLD_MASK,
+            fifo_config,
+        )
+        .await?;
+
+        let fifo_sel = (u8::from(fifo.decimation) << constants::BMP5_FIFO_DEC_SEL_POS)
+            | u8::from(fifo.frame_selection);
+        self.update_reg(
+            constants::BMP5_REG_FIFO_SEL,
+            constants::BMP5_FIFO_DEC_SEL_MASK | constants::BMP5_FIFO_FRAME_SEL_MASK,
+            fifo_sel,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 25 degC and 101325 Pa, as read from the data registers
+    const TEMPERATURE: [u8; 3] = [0x00, 0x00, 0x19];
+    const PRESSURE: [u8; 3] = [0x40, 0xF3, 0x62];
+    const EMPTY: [u8; 3] = [constants::BMP5_FIFO_EMPTY; 3];
+
+    #[test]
+    fn temperature_frames() {
+        // -10 degC
+        let data = [TEMPERATURE, [0x00, 0x00, 0xF6]].concat();
+        let frames: Vec<_> = FifoParser::new(&data, FifoFrameSelection::Temperature)
+            .map(|frame| frame.temperature())
+            .collect();
+        assert_eq!(frames, [Some(25.0), Some(-10.0)]);
+    }
+
+    #[test]
+    fn pressure_frames() {
+        let frames: Vec<_> = FifoParser::new(&PRESSURE, FifoFrameSelection::Pressure).collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].pressure(), Some(101325.0));
+        assert_eq!(frames[0].temperature(), None);
+    }
+
+    #[test]
+    fn pressure_and_temperature_frames() {
+        let data = [TEMPERATURE, PRESSURE, TEMPERATURE, PRESSURE].concat();
+        let frames: Vec<_> =
+            FifoParser::new(&data, FifoFrameSelection::PressureAndTemperature).collect();
+        assert_eq!(frames.len(), 2);
+        for frame in frames {
+            assert_eq!(frame.temperature(), Some(25.0));
+            assert_eq!(frame.pressure(), Some(101325.0));
+        }
+    }
+
+    #[test]
+    fn scaling_matches_measure() {
+        let data = [TEMPERATURE, PRESSURE].concat();
+        let measurement = Measurement::from_bytes(&data);
+        let frame = FifoParser::new(&data, FifoFrameSelection::PressureAndTemperature)
+            .next()
+            .unwrap();
+        assert_eq!(frame.temperature(), Some(measurement.temperature));
+        assert_eq!(frame.pressure(), Some(measurement.pressure));
+    }
+
+    #[test]
+    fn empty_frames_are_skipped() {
+        let data = [EMPTY, EMPTY, TEMPERATURE, PRESSURE, EMPTY, EMPTY].concat();
+        let frames: Vec<_> =
+            FifoParser::new(&data, FifoFrameSelection::PressureAndTemperature).collect();
+        assert_eq!(frames.len(), 1);
+
+        let data = [EMPTY, PRESSURE, EMPTY].concat();
+        assert_eq!(
+            FifoParser::new(&data, FifoFrameSelection::Pressure).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn trailing_partial_frame_is_ignored() {
+        let data = [TEMPERATURE, PRESSURE, TEMPERATURE].concat();
+        assert_eq!(
+            FifoParser::new(&data, FifoFrameSelection::PressureAndTemperature).count(),
+            1
+        );
+        assert_eq!(
+            FifoParser::new(&data[..4], FifoFrameSelection::Temperature).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn frame_selection_round_trip() {
+        crate::tests::assert_round_trip::<FifoFrameSelection>(4);
+    }
+
+    #[test]
+    fn decimation_round_trip() {
+        crate::tests::assert_round_trip::<FifoDecimation>(8);
+    }
+
+    #[test]
+    fn disabled_fifo_has_no_frames() {
+        let data = [TEMPERATURE, PRESSURE].concat();
+        assert_eq!(
+            FifoParser::new(&data, FifoFrameSelection::Disabled).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,34 @@
+#[maybe_async_cfg::maybe(
+    sync(self = "blocking", idents(embedded_hal_async(sync = "embedded_hal"))),
+    async(keep_self)
+)]
+pub mod asynch {
+    /// Register level access to a BMP5 sensor
+    ///
+    /// The driver only talks to the sensor through this trait. It is implemented
+    /// for the I2C and SPI transports in this crate, and can be implemented for any
+    /// other way of reaching the register map (e.g. a bridge on a co-processor).
+    #[allow(async_fn_in_trait)]
+    pub trait RegisterInterface {
+        /// Error returned by the underlying bus
+        type Error;
+
+        /// Read consecutive registers starting at `reg` into `buf`.
+        async fn read_registers(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+        /// Write `data` to consecutive registers starting at `reg`.
+        async fn write_registers(&mut self, reg: u8, data: &[u8]) -> Result<(), Self::Error>;
+
+        /// Read a single register.
+        async fn read_register(&mut self, reg: u8) -> Result<u8, Self::Error> {
+            let mut buf = [0u8];
+            self.read_registers(reg, &mut buf).await?;
+            Ok(buf[0])
+        }
+
+        /// Write a single register.
+        async fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Self::Error> {
+            self.write_registers(reg, &[value]).await
+        }
+    }
+}
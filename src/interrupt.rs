@@ -0,0 +1,324 @@
+use crate::{Error, constants, driver, interface};
+
+bitflags::bitflags! {
+    /// Interrupt sources
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct InterruptSource: u8 {
+        /// New measurement available
+        const DATA_READY = constants::BMP5_INT_ENABLE_DRDY;
+        /// FIFO full
+        const FIFO_FULL = constants::BMP5_INT_ENABLE_FIFO_FULL;
+        /// FIFO threshold reached
+        const FIFO_THRESHOLD = constants::BMP5_INT_ENABLE_FIFO_THRES;
+        /// Pressure out of range
+        const PRESSURE_OUT_OF_RANGE = constants::BMP5_INT_ENABLE_PRESSURE_OOR;
+    }
+}
+
+bitflags::bitflags! {
+    /// Interrupt status flags
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct InterruptStatus: u8 {
+        /// New measurement available
+        const DATA_READY = constants::BMP5_INT_STATUS_DRDY;
+        /// FIFO full
+        const FIFO_FULL = constants::BMP5_INT_STATUS_FIFO_FULL;
+        /// FIFO threshold reached
+        const FIFO_THRESHOLD = constants::BMP5_INT_STATUS_FIFO_THRES;
+        /// Pressure out of range
+        const PRESSURE_OUT_OF_RANGE = constants::BMP5_INT_STATUS_PRESSURE_OOR;
+        /// Power-on reset or soft reset complete
+        const POR_SOFTRESET_COMPLETE = constants::BMP5_INT_STATUS_POR_SOFTRESET_COMPLETE;
+    }
+}
+
+impl From<InterruptSource> for InterruptStatus {
+    fn from(sources: InterruptSource) -> Self {
+        Self::from_bits_truncate(sources.bits())
+    }
+}
+
+impl Default for InterruptSource {
+    /// Data ready only, as required by `measure`.
+    fn default() -> Self {
+        Self::DATA_READY
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for InterruptSource {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "InterruptSource({=u8:#x})", self.bits())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for InterruptStatus {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "InterruptStatus({=u8:#x})", self.bits())
+    }
+}
+
+/// Behaviour of the INT pin once an interrupt is raised
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptPinMode {
+    /// Short pulse per interrupt
+    #[default]
+    Pulsed,
+    /// Asserted until the interrupt status is read
+    Latched,
+}
+
+/// Active level of the INT pin
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptPinPolarity {
+    /// Low when asserted
+    #[default]
+    ActiveLow,
+    /// High when asserted
+    ActiveHigh,
+}
+
+/// Output stage of the INT pin
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptPinDrive {
+    /// Push-pull
+    #[default]
+    PushPull,
+    /// Open-drain, for interrupt lines shared with other devices
+    OpenDrain,
+}
+
+/// Electrical configuration of the INT pin
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InterruptPinConfig {
+    /// Drive the INT pin, when disabled it stays inactive
+    pub enabled: bool,
+    /// Pulsed or latched
+    pub mode: InterruptPinMode,
+    /// Active level
+    pub polarity: InterruptPinPolarity,
+    /// Push-pull or open-drain
+    pub drive: InterruptPinDrive,
+    /// Pad drive strength, 0 to 15
+    pub pad_drive_strength: u8,
+}
+
+impl Default for InterruptPinConfig {
+    /// Reset state of the sensor: disabled, pulsed, active low and
+    /// push-pull, with pad drive strength 3.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: InterruptPinMode::default(),
+            polarity: InterruptPinPolarity::default(),
+            drive: InterruptPinDrive::default(),
+            pad_drive_strength: 3,
+        }
+    }
+}
+
+impl From<InterruptPinConfig> for u8 {
+    fn from(config: InterruptPinConfig) -> u8 {
+        let mut int_config = config.pad_drive_strength << constants::BMP5_INT_CONFIG_PAD_DRV_POS;
+        if config.enabled {
+            int_config |= constants::BMP5_INT_CONFIG_EN;
+        }
+        if config.mode == InterruptPinMode::Latched {
+            int_config |= constants::BMP5_INT_CONFIG_MODE_LATCHED;
+        }
+        if config.polarity == InterruptPinPolarity::ActiveHigh {
+            int_config |= constants::BMP5_INT_CONFIG_POL_ACTIVE_HIGH;
+        }
+        if config.drive == InterruptPinDrive::OpenDrain {
+            int_config |= constants::BMP5_INT_CONFIG_OPEN_DRAIN;
+        }
+        int_config
+    }
+}
+
+impl From<u8> for InterruptPinConfig {
+    /// Decode the INT_CONFIG register.
+    fn from(int_config: u8) -> Self {
+        Self {
+            enabled: int_config & constants::BMP5_INT_CONFIG_EN != 0,
+            mode: if int_config & constants::BMP5_INT_CONFIG_MODE_LATCHED != 0 {
+                InterruptPinMode::Latched
+            } else {
+                InterruptPinMode::Pulsed
+            },
+            polarity: if int_config & constants::BMP5_INT_CONFIG_POL_ACTIVE_HIGH != 0 {
+                InterruptPinPolarity::ActiveHigh
+            } else {
+                InterruptPinPolarity::ActiveLow
+            },
+            drive: if int_config & constants::BMP5_INT_CONFIG_OPEN_DRAIN != 0 {
+                InterruptPinDrive::OpenDrain
+            } else {
+                InterruptPinDrive::PushPull
+            },
+            pad_drive_strength: int_config >> constants::BMP5_INT_CONFIG_PAD_DRV_POS,
+        }
+    }
+}
+
+/// Placeholder for a driver without an interrupt pin
+///
+/// The interrupt status register is polled instead.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoPin;
+
+impl embedded_hal::digital::ErrorType for NoPin {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_hal::digital::InputPin for NoPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+impl embedded_hal_async::digital::Wait for NoPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(
+            asynch(sync = "blocking"),
+            embedded_hal_async(sync = "embedded_hal"),
+            Wait(sync = "InputPin")
+        )
+    ),
+    async(keep_self)
+)]
+impl<I, D, P, E> driver::asynch::Bmp5<I, D, P>
+where
+    I: interface::asynch::RegisterInterface<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    P: embedded_hal_async::digital::Wait,
+{
+    /// Enable the given interrupt sources, in addition to those already enabled.
+    pub async fn enable_interrupts(&mut self, sources: InterruptSource) -> Result<(), Error<E>> {
+        self.config.interrupt_source |= sources;
+        self.write_interrupt_source().await
+    }
+
+    /// Disable the given interrupt sources.
+    ///
+    /// `measure` relies on [`InterruptSource::DATA_READY`] being enabled.
+    pub async fn disable_interrupts(&mut self, sources: InterruptSource) -> Result<(), Error<E>> {
+        self.config.interrupt_source &= !sources;
+        self.write_interrupt_source().await
+    }
+
+    /// Change the electrical configuration of the INT pin.
+    pub async fn set_interrupt_pin_config(
+        &mut self,
+        interrupt_pin: InterruptPinConfig,
+    ) -> Result<(), Error<E>> {
+        if interrupt_pin.pad_drive_strength > constants::BMP5_INT_CONFIG_MAX_PAD_DRV {
+            return Err(Error::InvalidConfig);
+        }
+
+        self.config.interrupt_pin = interrupt_pin;
+        self.write_interrupt_pin_config().await
+    }
+
+    pub(crate) async fn write_interrupt_pin_config(&mut self) -> Result<(), Error<E>> {
+        let interrupt_pin = self.config.interrupt_pin;
+        if interrupt_pin.pad_drive_strength > constants::BMP5_INT_CONFIG_MAX_PAD_DRV {
+            return Err(Error::InvalidConfig);
+        }
+
+        // Mask all sources and clear pending interrupts while the pin is
+        // reconfigured, so it doesn't glitch
+        self.update_reg(
+            constants::BMP5_REG_INT_SOURCE,
+            InterruptSource::all().bits(),
+            0,
+        )
+        .await?;
+        self.fetch_interrupt_status().await?;
+
+        self.update_reg(
+            constants::BMP5_REG_INT_CONFIG,
+            constants::BMP5_INT_CONFIG_PAD_DRV_MASK
+                | constants::BMP5_INT_CONFIG_EN
+                | constants::BMP5_INT_CONFIG_OPEN_DRAIN
+                | constants::BMP5_INT_CONFIG_POL_ACTIVE_HIGH
+                | constants::BMP5_INT_CONFIG_MODE_LATCHED,
+            interrupt_pin.into(),
+        )
+        .await?;
+
+        self.write_interrupt_source().await
+    }
+
+    /// Read and acknowledge the interrupt status.
+    ///
+    /// Reading the status register clears it on the sensor. Flags seen by
+    /// the driver while waiting for other interrupts are kept and reported
+    /// here as well. [`InterruptStatus::POR_SOFTRESET_COMPLETE`] reports an
+    /// unexpected reset of the sensor, whose configuration and power mode
+    /// have been restored by the time this returns.
+    pub async fn read_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        self.fetch_interrupt_status().await?;
+        self.recover_if_reset().await?;
+        let status = self.pending_interrupts;
+        self.pending_interrupts = InterruptStatus::empty();
+        Ok(status)
+    }
+
+    /// Read the status register, adding its flags to those not yet
+    /// acknowledged. Returns the flags just read.
+    ///
+    /// Reading clears the reset flag on the sensor, so an unexpected reset is
+    /// recorded here for [`recover_if_reset`](Self::recover_if_reset).
+    pub(crate) async fn fetch_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        let int_status = self.read_reg(constants::BMP5_REG_INT_STATUS).await?;
+        let int_status = InterruptStatus::from_bits_truncate(int_status);
+        if int_status.contains(InterruptStatus::POR_SOFTRESET_COMPLETE) {
+            self.reset_detected = true;
+        }
+        self.pending_interrupts |= int_status;
+        Ok(int_status)
+    }
+
+    pub(crate) async fn write_interrupt_source(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(
+            constants::BMP5_REG_INT_SOURCE,
+            InterruptSource::all().bits(),
+            self.config.interrupt_source.bits(),
+        )
+        .await
+    }
+}
@@ -1,12 +1,60 @@
 #![cfg_attr(not(test), no_std)]
 
+use byteorder::{ByteOrder, LittleEndian};
+
+mod builder;
 #[allow(dead_code)]
 mod constants;
+pub mod diagnostics;
+mod driver;
+pub mod fifo;
 pub mod i2c;
+mod interface;
+mod interrupt;
+#[cfg(test)]
+mod mock;
+pub mod nvm;
+pub mod oor;
+pub mod spi;
+pub mod typestate;
 
-/// Temperature/pressure oversampling
+pub use builder::ConfigBuilder;
+pub use driver::asynch::Bmp5;
+pub use interface::asynch::RegisterInterface;
+pub use interrupt::{
+    InterruptPinConfig, InterruptPinDrive, InterruptPinMode, InterruptPinPolarity, InterruptSource,
+    InterruptStatus, NoPin,
+};
+
+/// Blocking driver built on the `embedded_hal` 1.0 traits
+///
+/// Generated from the same source as the async driver, so both behave
+/// identically.
+pub mod blocking {
+    pub use crate::driver::blocking::Bmp5;
+    pub use crate::interface::blocking::RegisterInterface;
+    pub use crate::typestate::blocking as typestate;
+}
+
+/// BMP5 driver error
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    Bus(E),
+    InvalidChipId(u8),
+    InvalidConfig,
+    Pin(embedded_hal::digital::ErrorKind),
+    Timeout,
+    Nvm(nvm::NvmError),
+    DegradedOversampling {
+        requested: OversamplingSettings,
+        effective: OversamplingSettings,
+    },
+}
+
+/// Temperature/pressure oversampling
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Oversampling {
     /// 1x oversampling, 0.78 Pa RMS noise, max rate 498 Hz
     Oversampling1X,
@@ -26,6 +74,37 @@ pub enum Oversampling {
     Oversampling128x,
 }
 
+impl Oversampling {
+    /// RMS pressure noise in Pascals, before IIR filtering
+    pub const fn pressure_noise_pa(self) -> f32 {
+        match self {
+            Oversampling::Oversampling1X => 0.78,
+            Oversampling::Oversampling2X => 0.58,
+            Oversampling::Oversampling4X => 0.41,
+            Oversampling::Oversampling8X => 0.30,
+            Oversampling::Oversampling16x => 0.21,
+            Oversampling::Oversampling32x => 0.15,
+            Oversampling::Oversampling64x => 0.11,
+            Oversampling::Oversampling128x => 0.08,
+        }
+    }
+
+    /// Number of samples averaged into a single measurement
+    pub const fn samples(self) -> u32 {
+        match self {
+            Oversampling::Oversampling1X => 1,
+            Oversampling::Oversampling2X => 2,
+            Oversampling::Oversampling4X => 4,
+            Oversampling::Oversampling8X => 8,
+            Oversampling::Oversampling16x => 16,
+            Oversampling::Oversampling32x => 32,
+            Oversampling::Oversampling64x => 64,
+            Oversampling::Oversampling128x => 128,
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for Oversampling {
     fn default() -> Self {
         Self::Oversampling1X
@@ -47,8 +126,52 @@ impl From<Oversampling> for u8 {
     }
 }
 
+impl TryFrom<u8> for Oversampling {
+    type Error = u8;
+
+    /// Oversampling for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            constants::BMP5_OSR_1X => Ok(Oversampling::Oversampling1X),
+            constants::BMP5_OSR_2X => Ok(Oversampling::Oversampling2X),
+            constants::BMP5_OSR_4X => Ok(Oversampling::Oversampling4X),
+            constants::BMP5_OSR_8X => Ok(Oversampling::Oversampling8X),
+            constants::BMP5_OSR_16X => Ok(Oversampling::Oversampling16x),
+            constants::BMP5_OSR_32X => Ok(Oversampling::Oversampling32x),
+            constants::BMP5_OSR_64X => Ok(Oversampling::Oversampling64x),
+            constants::BMP5_OSR_128X => Ok(Oversampling::Oversampling128x),
+            code => Err(code),
+        }
+    }
+}
+
+/// Temperature and pressure oversampling
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OversamplingSettings {
+    /// Temperature oversampling
+    pub temperature: Oversampling,
+    /// Pressure oversampling
+    pub pressure: Oversampling,
+}
+
+impl OversamplingSettings {
+    /// Decode the temperature and pressure fields of the OSR registers.
+    pub(crate) fn from_bits(bits: u8) -> Self {
+        // All 3 bit codes are valid
+        Self {
+            temperature: Oversampling::try_from(bits & constants::BMP5_OSR_TEMP_MASK)
+                .unwrap_or_default(),
+            pressure: Oversampling::try_from(
+                (bits & constants::BMP5_OSR_PRESS_MASK) >> constants::BMP5_OSR_PRESS_POS,
+            )
+            .unwrap_or_default(),
+        }
+    }
+}
+
 /// IIR low-pass filter
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum IIRFilter {
     /// No filtering
@@ -84,14 +207,34 @@ impl From<IIRFilter> for u8 {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for IIRFilter {
     fn default() -> Self {
         Self::Bypass
     }
 }
 
+impl TryFrom<u8> for IIRFilter {
+    type Error = u8;
+
+    /// IIR filter for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            constants::BMP5_IIR_FILTER_BYPASS => Ok(IIRFilter::Bypass),
+            constants::BMP5_IIR_FILTER_COEFF_1 => Ok(IIRFilter::Coeff1),
+            constants::BMP5_IIR_FILTER_COEFF_3 => Ok(IIRFilter::Coeff3),
+            constants::BMP5_IIR_FILTER_COEFF_7 => Ok(IIRFilter::Coeff7),
+            constants::BMP5_IIR_FILTER_COEFF_15 => Ok(IIRFilter::Coeff15),
+            constants::BMP5_IIR_FILTER_COEFF_31 => Ok(IIRFilter::Coeff31),
+            constants::BMP5_IIR_FILTER_COEFF_63 => Ok(IIRFilter::Coeff63),
+            constants::BMP5_IIR_FILTER_COEFF_127 => Ok(IIRFilter::Coeff127),
+            code => Err(code),
+        }
+    }
+}
+
 /// Output data rate
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OutputDataRate {
     /// 0.125 Hz
@@ -161,7 +304,7 @@ pub enum OutputDataRate {
 }
 
 impl OutputDataRate {
-    pub fn period_us(self) -> u32 {
+    pub const fn period_us(self) -> u32 {
         match self {
             OutputDataRate::OutputDataRate0_125Hz => 8_000_000,
             OutputDataRate::OutputDataRate0_250Hz => 4_000_000,
@@ -238,14 +381,58 @@ impl From<OutputDataRate> for u8 {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for OutputDataRate {
     fn default() -> Self {
         Self::OutputDataRate25Hz
     }
 }
 
+impl TryFrom<u8> for OutputDataRate {
+    type Error = u8;
+
+    /// Output data rate for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            constants::BMP5_ODR_0_125_HZ => Ok(OutputDataRate::OutputDataRate0_125Hz),
+            constants::BMP5_ODR_0_250_HZ => Ok(OutputDataRate::OutputDataRate0_250Hz),
+            constants::BMP5_ODR_0_5_HZ => Ok(OutputDataRate::OutputDataRate0_5Hz),
+            constants::BMP5_ODR_01_HZ => Ok(OutputDataRate::OutputDataRate1Hz),
+            constants::BMP5_ODR_02_HZ => Ok(OutputDataRate::OutputDataRate2Hz),
+            constants::BMP5_ODR_03_HZ => Ok(OutputDataRate::OutputDataRate3Hz),
+            constants::BMP5_ODR_04_HZ => Ok(OutputDataRate::OutputDataRate4Hz),
+            constants::BMP5_ODR_05_HZ => Ok(OutputDataRate::OutputDataRate5Hz),
+            constants::BMP5_ODR_10_HZ => Ok(OutputDataRate::OutputDataRate10Hz),
+            constants::BMP5_ODR_15_HZ => Ok(OutputDataRate::OutputDataRate15Hz),
+            constants::BMP5_ODR_20_HZ => Ok(OutputDataRate::OutputDataRate20Hz),
+            constants::BMP5_ODR_25_HZ => Ok(OutputDataRate::OutputDataRate25Hz),
+            constants::BMP5_ODR_30_HZ => Ok(OutputDataRate::OutputDataRate30Hz),
+            constants::BMP5_ODR_35_HZ => Ok(OutputDataRate::OutputDataRate35Hz),
+            constants::BMP5_ODR_40_HZ => Ok(OutputDataRate::OutputDataRate40Hz),
+            constants::BMP5_ODR_45_HZ => Ok(OutputDataRate::OutputDataRate45Hz),
+            constants::BMP5_ODR_50_HZ => Ok(OutputDataRate::OutputDataRate50Hz),
+            constants::BMP5_ODR_60_HZ => Ok(OutputDataRate::OutputDataRate60Hz),
+            constants::BMP5_ODR_70_HZ => Ok(OutputDataRate::OutputDataRate70Hz),
+            constants::BMP5_ODR_80_HZ => Ok(OutputDataRate::OutputDataRate80Hz),
+            constants::BMP5_ODR_89_6_HZ => Ok(OutputDataRate::OutputDataRate89_6Hz),
+            constants::BMP5_ODR_100_2_HZ => Ok(OutputDataRate::OutputDataRate100_2Hz),
+            constants::BMP5_ODR_110_1_HZ => Ok(OutputDataRate::OutputDataRate110_1Hz),
+            constants::BMP5_ODR_120_HZ => Ok(OutputDataRate::OutputDataRate120Hz),
+            constants::BMP5_ODR_129_8_HZ => Ok(OutputDataRate::OutputDataRate129_8Hz),
+            constants::BMP5_ODR_140_HZ => Ok(OutputDataRate::OutputDataRate140Hz),
+            constants::BMP5_ODR_149_3_HZ => Ok(OutputDataRate::OutputDataRate149_3Hz),
+            constants::BMP5_ODR_160_HZ => Ok(OutputDataRate::OutputDataRate160Hz),
+            constants::BMP5_ODR_179_2_HZ => Ok(OutputDataRate::OutputDataRate179_2Hz),
+            constants::BMP5_ODR_199_1_HZ => Ok(OutputDataRate::OutputDataRate199_1Hz),
+            constants::BMP5_ODR_218_5_HZ => Ok(OutputDataRate::OutputDataRate218_5Hz),
+            constants::BMP5_ODR_240_HZ => Ok(OutputDataRate::OutputDataRate240Hz),
+            code => Err(code),
+        }
+    }
+}
+
 /// Configuration
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Config {
     /// Temperature oversampling
@@ -258,6 +445,283 @@ pub struct Config {
     pub pressure_iir_filter: IIRFilter,
     /// Output data rate
     pub output_data_rate: OutputDataRate,
+    /// FIFO
+    pub fifo: fifo::FifoConfig,
+    /// Enabled interrupt sources
+    pub interrupt_source: InterruptSource,
+    /// INT pin configuration
+    pub interrupt_pin: InterruptPinConfig,
+    /// Pressure out-of-range window
+    pub pressure_oor: oor::OorConfig,
+    /// Maximum time to wait for an interrupt in microseconds
+    ///
+    /// When `None`, measurements time out after a few ODR periods, or forced
+    /// conversion times, while FIFO and out-of-range events are waited for
+    /// indefinitely.
+    pub timeout_us: Option<u32>,
+}
+
+impl Default for Config {
+    /// 1x oversampling at 25 Hz without filters or FIFO, data ready
+    /// interrupt only and the INT pin in its reset state.
+    fn default() -> Self {
+        Self::BASE
+    }
+}
+
+/// Presets for common use cases
+///
+/// Noise is the RMS pressure noise before IIR filtering. Current is the
+/// average in normal mode, estimated from the conversion time with about
+/// 0.6 mA drawn while converting.
+impl Config {
+    /// Default values, usable in const context
+    const BASE: Config = Config {
+        temperature_oversampling: Oversampling::Oversampling1X,
+        temperature_iir_filter: IIRFilter::Bypass,
+        pressure_oversampling: Oversampling::Oversampling1X,
+        pressure_iir_filter: IIRFilter::Bypass,
+        output_data_rate: OutputDataRate::OutputDataRate25Hz,
+        fifo: fifo::FifoConfig {
+            frame_selection: fifo::FifoFrameSelection::Disabled,
+            mode: fifo::FifoMode::Streaming,
+            decimation: fifo::FifoDecimation::Decimation1X,
+            threshold: 0,
+        },
+        interrupt_source: InterruptSource::DATA_READY,
+        interrupt_pin: InterruptPinConfig {
+            enabled: false,
+            mode: InterruptPinMode::Pulsed,
+            polarity: InterruptPinPolarity::ActiveLow,
+            drive: InterruptPinDrive::PushPull,
+            pad_drive_strength: 3,
+        },
+        pressure_oor: oor::OorConfig {
+            center: 0,
+            range: 0,
+            count_limit: oor::OorCountLimit::Count1,
+        },
+        timeout_us: None,
+    };
+
+    /// Lowest power: 1x oversampling at 1 Hz, 0.78 Pa noise, about 1.3 µA
+    pub const LOWEST_POWER: Config = Config {
+        output_data_rate: OutputDataRate::OutputDataRate1Hz,
+        ..Self::BASE
+    };
+
+    /// Standard resolution: 4x pressure oversampling at 10 Hz, 0.41 Pa noise,
+    /// about 23 µA
+    pub const STANDARD_RESOLUTION: Config = Config {
+        pressure_oversampling: Oversampling::Oversampling4X,
+        output_data_rate: OutputDataRate::OutputDataRate10Hz,
+        ..Self::BASE
+    };
+
+    /// High resolution: 16x pressure oversampling at 10 Hz, 0.21 Pa noise,
+    /// about 69 µA
+    pub const HIGH_RESOLUTION: Config = Config {
+        pressure_oversampling: Oversampling::Oversampling16x,
+        output_data_rate: OutputDataRate::OutputDataRate10Hz,
+        ..Self::BASE
+    };
+
+    /// Ultra high resolution: 128x pressure and 8x temperature oversampling
+    /// at 5 Hz, 0.08 Pa noise, about 250 µA
+    pub const ULTRA_HIGH_RESOLUTION: Config = Config {
+        temperature_oversampling: Oversampling::Oversampling8X,
+        pressure_oversampling: Oversampling::Oversampling128x,
+        output_data_rate: OutputDataRate::OutputDataRate5Hz,
+        ..Self::BASE
+    };
+
+    /// Indoor navigation: 32x pressure and 2x temperature oversampling with
+    /// light pressure filtering at 25 Hz, 0.15 Pa noise, about 330 µA
+    pub const INDOOR_NAVIGATION: Config = Config {
+        temperature_oversampling: Oversampling::Oversampling2X,
+        pressure_oversampling: Oversampling::Oversampling32x,
+        pressure_iir_filter: IIRFilter::Coeff3,
+        output_data_rate: OutputDataRate::OutputDataRate25Hz,
+        ..Self::BASE
+    };
+
+    /// Drone and fast altitude changes: 8x pressure oversampling with
+    /// minimal filtering at 100 Hz, 0.30 Pa noise, about 390 µA
+    pub const DRONE: Config = Config {
+        pressure_oversampling: Oversampling::Oversampling8X,
+        pressure_iir_filter: IIRFilter::Coeff1,
+        output_data_rate: OutputDataRate::OutputDataRate100_2Hz,
+        ..Self::BASE
+    };
+
+    /// Weather monitoring: 1x oversampling at 0.125 Hz, 0.78 Pa noise, about
+    /// 0.15 µA plus the standby current. Also suited to forced measurements
+    /// from deep standby.
+    pub const WEATHER_MONITORING: Config = Config {
+        output_data_rate: OutputDataRate::OutputDataRate0_125Hz,
+        ..Self::BASE
+    };
+}
+
+// Presets are valid by construction
+const _: () = {
+    assert!(Config::LOWEST_POWER.validate().is_ok());
+    assert!(Config::STANDARD_RESOLUTION.validate().is_ok());
+    assert!(Config::HIGH_RESOLUTION.validate().is_ok());
+    assert!(Config::ULTRA_HIGH_RESOLUTION.validate().is_ok());
+    assert!(Config::INDOOR_NAVIGATION.validate().is_ok());
+    assert!(Config::DRONE.validate().is_ok());
+    assert!(Config::WEATHER_MONITORING.validate().is_ok());
+};
+
+impl Config {
+    /// Requested temperature and pressure oversampling
+    pub const fn oversampling(&self) -> OversamplingSettings {
+        OversamplingSettings {
+            temperature: self.temperature_oversampling,
+            pressure: self.pressure_oversampling,
+        }
+    }
+
+    /// Approximate duration of a single conversion in microseconds
+    ///
+    /// Derived from the maximum output data rates the datasheet lists for
+    /// each pressure oversampling with temperature at 1x. The cost of higher
+    /// temperature oversampling is an estimate.
+    pub const fn conversion_time_us(&self) -> u32 {
+        constants::BMP5_CONV_TIME_BASE_US
+            + self.pressure_oversampling.samples() * constants::BMP5_CONV_TIME_PRESS_US
+            + self.temperature_oversampling.samples() * constants::BMP5_CONV_TIME_TEMP_US
+    }
+
+    /// Estimated average current in normal mode in microamperes
+    ///
+    /// Based on the conversion time model and the current drawn while
+    /// converting, standby current isn't included.
+    pub fn estimated_current_ua(&self) -> f32 {
+        self.conversion_time_us() as f32 * constants::BMP5_ACTIVE_CURRENT_UA
+            / self.output_data_rate.period_us() as f32
+    }
+
+    /// Check the configuration without talking to the sensor.
+    ///
+    /// Uses the same conversion time model as
+    /// [`conversion_time_us`](Self::conversion_time_us), so the sensor may
+    /// still accept combinations right at the limit. Can be evaluated at
+    /// compile time, e.g. `const _: () = assert!(CONFIG.validate().is_ok());`.
+    pub const fn validate(&self) -> Result<(), ConfigError> {
+        let conversion_time_us = self.conversion_time_us();
+        let period_us = self.output_data_rate.period_us();
+        if conversion_time_us > period_us {
+            return Err(ConfigError::OutputDataRateTooHigh {
+                conversion_time_us,
+                period_us,
+            });
+        }
+        self.validate_registers()
+    }
+
+    /// The checks of [`validate`](Self::validate) for values that don't fit
+    /// their registers. Whether the sensor sustains the output data rate is
+    /// left to it.
+    pub(crate) const fn validate_registers(&self) -> Result<(), ConfigError> {
+        if self.fifo.threshold > self.fifo.max_threshold() {
+            return Err(ConfigError::FifoThresholdTooHigh);
+        }
+        if self.pressure_oor.center > constants::BMP5_OOR_MAX_THRESHOLD {
+            return Err(ConfigError::OorCenterTooHigh);
+        }
+        if self.interrupt_pin.pad_drive_strength > constants::BMP5_INT_CONFIG_MAX_PAD_DRV {
+            return Err(ConfigError::PadDriveStrengthTooHigh);
+        }
+        Ok(())
+    }
+}
+
+/// Reason a configuration is rejected by [`Config::validate`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// A conversion with the selected oversampling takes longer than the
+    /// output data rate period
+    OutputDataRateTooHigh {
+        /// Conversion time in microseconds
+        conversion_time_us: u32,
+        /// Output data rate period in microseconds
+        period_us: u32,
+    },
+    /// FIFO threshold above the capacity for the frame selection
+    FifoThresholdTooHigh,
+    /// Out-of-range window centre above 131071 Pa
+    OorCenterTooHigh,
+    /// INT pin pad drive strength above 15
+    PadDriveStrengthTooHigh,
+    /// Requested rate above the fastest output data rate
+    RateUnavailable,
+    /// No oversampling meets the rate, noise and current limits together
+    NoOversamplingFits,
+}
+
+/// Power mode
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PowerMode {
+    /// No measurements are taken
+    Standby,
+    /// Lowest power standby, requires an ODR of 5 Hz or below and the IIR
+    /// filters and FIFO to be disabled
+    DeepStandby,
+    /// Measurements are taken at the configured output data rate
+    Normal,
+    /// A single measurement is taken, then the sensor returns to standby
+    Forced,
+    /// Measurements are taken back to back, ignoring the output data rate
+    Continuous,
+}
+
+/// Sensor variant
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChipVariant {
+    /// BMP580 or BMP581
+    Bmp581,
+    /// BMP585
+    Bmp585,
+}
+
+impl From<ChipVariant> for u8 {
+    /// Chip id of the variant
+    fn from(variant: ChipVariant) -> u8 {
+        match variant {
+            ChipVariant::Bmp581 => constants::BMP5_CHIP_ID,
+            ChipVariant::Bmp585 => constants::BMP5_CHIP_ID_ALT,
+        }
+    }
+}
+
+impl TryFrom<u8> for ChipVariant {
+    type Error = u8;
+
+    /// Variant for a chip id, the unknown id is returned on failure.
+    fn try_from(chip_id: u8) -> Result<Self, u8> {
+        match chip_id {
+            constants::BMP5_CHIP_ID => Ok(ChipVariant::Bmp581),
+            constants::BMP5_CHIP_ID_ALT => Ok(ChipVariant::Bmp585),
+            chip_id => Err(chip_id),
+        }
+    }
+}
+
+/// Sensor identification
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChipInfo {
+    /// Sensor variant
+    pub variant: ChipVariant,
+    /// Raw chip id
+    pub chip_id: u8,
+    /// Silicon revision id
+    pub revision_id: u8,
 }
 
 /// Measurement data
@@ -269,3 +733,229 @@ pub struct Measurement {
     /// Pressure in Pascals
     pub pressure: f32,
 }
+
+impl Measurement {
+    /// Decode temperature followed by pressure, as laid out in the data
+    /// registers and in FIFO frames
+    pub(crate) fn from_bytes(buf: &[u8]) -> Self {
+        Self {
+            temperature: temperature_from_bytes(buf),
+            pressure: pressure_from_bytes(&buf[3..]),
+        }
+    }
+}
+
+/// Decode a 24-bit raw temperature into degrees Celsius
+pub(crate) fn temperature_from_bytes(buf: &[u8]) -> f32 {
+    LittleEndian::read_i24(buf) as f32 / 65536.0
+}
+
+/// Decode a 24-bit raw pressure into Pascals
+pub(crate) fn pressure_from_bytes(buf: &[u8]) -> f32 {
+    LittleEndian::read_i24(buf) as f32 / 64.0
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::fifo::{FifoConfig, FifoFrameSelection};
+    use crate::oor::OorConfig;
+
+    /// Check that every code accepted by `TryFrom<u8>` converts back to
+    /// itself, and that exactly `valid` codes are accepted.
+    pub(crate) fn assert_round_trip<T>(valid: usize)
+    where
+        T: TryFrom<u8, Error = u8>,
+        u8: From<T>,
+    {
+        let mut accepted = 0;
+        for code in 0..=u8::MAX {
+            match T::try_from(code) {
+                Ok(value) => {
+                    assert_eq!(u8::from(value), code);
+                    accepted += 1;
+                }
+                Err(invalid) => assert_eq!(invalid, code),
+            }
+        }
+        assert_eq!(accepted, valid);
+    }
+
+    #[test]
+    fn default_config_matches_field_defaults() {
+        assert_eq!(
+            Config::default(),
+            Config {
+                temperature_oversampling: Oversampling::default(),
+                temperature_iir_filter: IIRFilter::default(),
+                pressure_oversampling: Oversampling::default(),
+                pressure_iir_filter: IIRFilter::default(),
+                output_data_rate: OutputDataRate::default(),
+                fifo: FifoConfig::default(),
+                interrupt_source: InterruptSource::default(),
+                interrupt_pin: InterruptPinConfig::default(),
+                pressure_oor: OorConfig::default(),
+                timeout_us: None,
+            }
+        );
+    }
+
+    #[test]
+    fn oversampling_round_trip() {
+        assert_round_trip::<Oversampling>(8);
+    }
+
+    #[test]
+    fn iir_filter_round_trip() {
+        assert_round_trip::<IIRFilter>(8);
+    }
+
+    #[test]
+    fn output_data_rate_round_trip() {
+        assert_round_trip::<OutputDataRate>(32);
+    }
+
+    #[test]
+    fn chip_variant_round_trip() {
+        assert_round_trip::<ChipVariant>(2);
+    }
+
+    /// The datasheet table only covers temperature at 1x, so this checks the
+    /// pressure term and the sum of the base and temperature terms.
+    #[test]
+    fn conversion_time_matches_datasheet_rates() {
+        let max_rates_hz = [
+            (Oversampling::Oversampling1X, 498.0),
+            (Oversampling::Oversampling2X, 374.0),
+            (Oversampling::Oversampling4X, 255.0),
+            (Oversampling::Oversampling8X, 155.0),
+            (Oversampling::Oversampling16x, 87.0),
+            (Oversampling::Oversampling32x, 46.0),
+            (Oversampling::Oversampling64x, 24.0),
+            (Oversampling::Oversampling128x, 12.0),
+        ];
+        for (pressure_oversampling, max_rate_hz) in max_rates_hz {
+            let config = Config {
+                pressure_oversampling,
+                ..Config::default()
+            };
+            let rate_hz = 1e6 / config.conversion_time_us() as f32;
+            assert!(
+                (rate_hz / max_rate_hz - 1.0).abs() < 0.02,
+                "{pressure_oversampling:?}: {rate_hz} Hz, datasheet {max_rate_hz} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_output_data_rate() {
+        let config = Config {
+            pressure_oversampling: Oversampling::Oversampling16x,
+            output_data_rate: OutputDataRate::OutputDataRate25Hz,
+            ..Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = Config {
+            pressure_oversampling: Oversampling::Oversampling128x,
+            ..config
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::OutputDataRateTooHigh {
+                conversion_time_us: 82020,
+                period_us: 40000,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_fifo_threshold() {
+        let fifo = FifoConfig {
+            frame_selection: FifoFrameSelection::PressureAndTemperature,
+            threshold: 15,
+            ..FifoConfig::default()
+        };
+        let config = Config {
+            fifo,
+            ..Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = Config {
+            fifo: FifoConfig {
+                threshold: 16,
+                ..fifo
+            },
+            ..config
+        };
+        assert_eq!(config.validate(), Err(ConfigError::FifoThresholdTooHigh));
+
+        let config = Config {
+            fifo: FifoConfig {
+                frame_selection: FifoFrameSelection::Pressure,
+                threshold: 31,
+                ..fifo
+            },
+            ..config
+        };
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_oor_center() {
+        let config = Config {
+            pressure_oor: OorConfig {
+                center: 0x1FFFF,
+                ..OorConfig::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = Config {
+            pressure_oor: OorConfig {
+                center: 0x20000,
+                ..config.pressure_oor
+            },
+            ..config
+        };
+        assert_eq!(config.validate(), Err(ConfigError::OorCenterTooHigh));
+    }
+
+    #[test]
+    fn validate_pad_drive_strength() {
+        let config = Config {
+            interrupt_pin: InterruptPinConfig {
+                pad_drive_strength: 15,
+                ..InterruptPinConfig::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = Config {
+            interrupt_pin: InterruptPinConfig {
+                pad_drive_strength: 16,
+                ..config.interrupt_pin
+            },
+            ..config
+        };
+        assert_eq!(config.validate(), Err(ConfigError::PadDriveStrengthTooHigh));
+    }
+
+    #[test]
+    fn oversampling_settings_from_bits() {
+        let bits = (constants::BMP5_OSR_64X << constants::BMP5_OSR_PRESS_POS)
+            | constants::BMP5_OSR_2X
+            | constants::BMP5_OSR_PRESS_EN
+            | constants::BMP5_OSR_EFF_VALID_ODR;
+        assert_eq!(
+            OversamplingSettings::from_bits(bits),
+            OversamplingSettings {
+                temperature: Oversampling::Oversampling2X,
+                pressure: Oversampling::Oversampling64x,
+            }
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Register map of a simulated sensor, for testing the driver without
+//! hardware
+
+use crate::constants;
+use core::cell::RefCell;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Register map and the parts of the sensor behaviour the driver relies on
+pub(crate) struct Registers {
+    pub(crate) regs: [u8; 128],
+    pub(crate) nvm: [u16; 64],
+    /// Every register write, in order
+    pub(crate) writes: Vec<(u8, u8)>,
+    /// Writes to this register fail, once the given number of them succeeded
+    pub(crate) fail_writes_to: Option<(u8, usize)>,
+    /// Reported in OSR_EFF
+    pub(crate) odr_valid: bool,
+    /// Status reads the NVM stays busy after a command
+    pub(crate) nvm_busy_reads: u32,
+    /// Programming sets the NVM error flag instead of storing the word
+    pub(crate) nvm_fails: bool,
+    /// Programming stores a different word than requested
+    pub(crate) nvm_corrupts: bool,
+    nvm_busy: u32,
+    nvm_unlocked: bool,
+}
+
+impl Registers {
+    fn new() -> Self {
+        let mut registers = Self {
+            regs: [0; 128],
+            nvm: [0; 64],
+            writes: Vec::new(),
+            fail_writes_to: None,
+            odr_valid: true,
+            nvm_busy_reads: 0,
+            nvm_fails: false,
+            nvm_corrupts: false,
+            nvm_busy: 0,
+            nvm_unlocked: false,
+        };
+        registers.reset();
+        registers
+    }
+
+    /// Power-on reset, as after a brown-out.
+    pub(crate) fn reset(&mut self) {
+        self.regs = [0; 128];
+        self.regs[constants::BMP5_REG_CHIP_ID as usize] = constants::BMP5_CHIP_ID;
+        self.regs[constants::BMP5_REG_REV_ID as usize] = 0x32;
+        self.regs[constants::BMP5_REG_INT_CONFIG as usize] = 0x30;
+        self.regs[constants::BMP5_REG_INT_STATUS as usize] =
+            constants::BMP5_INT_STATUS_POR_SOFTRESET_COMPLETE;
+        self.regs[constants::BMP5_REG_STATUS as usize] =
+            constants::BMP5_STATUS_CORE_RDY | constants::BMP5_STATUS_NVM_RDY;
+        self.regs[constants::BMP5_REG_ODR_CONFIG as usize] = 0x70;
+
+        // 25 degC and 101325 Pa
+        self.regs[0x1D..0x23].copy_from_slice(&[0x00, 0x00, 0x19, 0x40, 0xF3, 0x62]);
+    }
+
+    pub(crate) fn reg(&self, reg: u8) -> u8 {
+        self.regs[reg as usize]
+    }
+
+    fn mode(&self) -> u8 {
+        self.reg(constants::BMP5_REG_ODR_CONFIG) & constants::BMP5_MODE_MASK
+    }
+
+    /// Raise an interrupt, if its source is enabled.
+    fn raise(&mut self, status: u8) {
+        if self.reg(constants::BMP5_REG_INT_SOURCE) & status != 0 {
+            self.regs[constants::BMP5_REG_INT_STATUS as usize] |= status;
+        }
+    }
+
+    fn read(&mut self, reg: u8) -> u8 {
+        match reg {
+            constants::BMP5_REG_INT_STATUS => {
+                // A new sample is ready every time in normal and continuous mode
+                if matches!(
+                    self.mode(),
+                    constants::BMP5_MODE_NORMAL | constants::BMP5_MODE_CONTINOUS
+                ) {
+                    self.raise(constants::BMP5_INT_STATUS_DRDY);
+                }
+                core::mem::take(&mut self.regs[reg as usize])
+            }
+            constants::BMP5_REG_STATUS => {
+                let mut status = self.reg(reg);
+                if self.nvm_busy > 0 {
+                    self.nvm_busy -= 1;
+                    status &= !constants::BMP5_STATUS_NVM_RDY;
+                }
+                status
+            }
+            constants::BMP5_REG_OSR_EFF => {
+                let osr = self.reg(constants::BMP5_REG_OSR_CONFIG) & constants::BMP5_OSR_MASK;
+                if self.odr_valid {
+                    osr | constants::BMP5_OSR_EFF_VALID_ODR
+                } else {
+                    // Pressure oversampling falls back to 1x
+                    osr & !constants::BMP5_OSR_PRESS_MASK
+                }
+            }
+            reg => self.reg(reg),
+        }
+    }
+
+    fn write(&mut self, reg: u8, value: u8) {
+        self.writes.push((reg, value));
+        match reg {
+            constants::BMP5_REG_CMD => self.command(value),
+            constants::BMP5_REG_ODR_CONFIG => {
+                self.regs[reg as usize] = value;
+                if self.mode() == constants::BMP5_MODE_FORCED {
+                    // Single conversion, then back to standby
+                    self.regs[reg as usize] &= !constants::BMP5_MODE_MASK;
+                    self.raise(constants::BMP5_INT_STATUS_DRDY);
+                }
+            }
+            reg => self.regs[reg as usize] = value,
+        }
+    }
+
+    fn command(&mut self, cmd: u8) {
+        let unlocked = core::mem::take(&mut self.nvm_unlocked);
+        let nvm_addr = self.reg(constants::BMP5_REG_NVM_ADDR);
+        let addr = (nvm_addr & constants::BMP5_NVM_ADDR_MASK) as usize;
+        match cmd {
+            constants::BMP5_CMD_SOFT_RESET => self.reset(),
+            constants::BMP5_CMD_NVM_OP_SEQ_0 => self.nvm_unlocked = true,
+            constants::BMP5_CMD_NVM_READ_SEQ_1 if unlocked => {
+                let [lsb, msb] = self.nvm[addr].to_le_bytes();
+                self.regs[constants::BMP5_REG_NVM_DATA_LSB as usize] = lsb;
+                self.regs[constants::BMP5_REG_NVM_DATA_MSB as usize] = msb;
+                self.nvm_busy = self.nvm_busy_reads;
+            }
+            constants::BMP5_CMD_NVM_WRITE_SEQ_1
+                if unlocked && nvm_addr & constants::BMP5_NVM_PROG_EN != 0 =>
+            {
+                if self.nvm_fails {
+                    self.regs[constants::BMP5_REG_STATUS as usize] |=
+                        constants::BMP5_STATUS_NVM_ERR;
+                } else {
+                    let lsb = self.reg(constants::BMP5_REG_NVM_DATA_LSB);
+                    let msb = self.reg(constants::BMP5_REG_NVM_DATA_MSB);
+                    self.nvm[addr] = u16::from_le_bytes([lsb, msb]) ^ self.nvm_corrupts as u16;
+                }
+                self.nvm_busy = self.nvm_busy_reads;
+            }
+            _ => {
+                self.regs[constants::BMP5_REG_STATUS as usize] |=
+                    constants::BMP5_STATUS_NVM_CMD_ERR;
+            }
+        }
+    }
+}
+
+/// Register interface to a simulated sensor, clones share the register map
+#[derive(Clone)]
+pub(crate) struct Mock(pub(crate) Rc<RefCell<Registers>>);
+
+impl Mock {
+    pub(crate) fn new() -> Self {
+        Self(Rc::new(RefCell::new(Registers::new())))
+    }
+
+    /// Blocking driver on top of the simulated sensor.
+    pub(crate) fn driver(
+        &self,
+        config: crate::Config,
+    ) -> crate::blocking::Bmp5<Mock, NoDelay, crate::NoPin> {
+        crate::blocking::Bmp5::from_interface(self.clone(), NoDelay, config)
+    }
+
+    pub(crate) fn reg(&self, reg: u8) -> u8 {
+        self.0.borrow().reg(reg)
+    }
+
+    /// Register writes since the last call.
+    pub(crate) fn writes(&self) -> Vec<(u8, u8)> {
+        core::mem::take(&mut self.0.borrow_mut().writes)
+    }
+}
+
+/// Bus error of the simulated sensor
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct MockError;
+
+impl crate::blocking::RegisterInterface for Mock {
+    type Error = MockError;
+
+    fn read_registers(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), MockError> {
+        let mut registers = self.0.borrow_mut();
+        for (offset, value) in buf.iter_mut().enumerate() {
+            *value = registers.read(reg + offset as u8);
+        }
+        Ok(())
+    }
+
+    fn write_registers(&mut self, reg: u8, data: &[u8]) -> Result<(), MockError> {
+        let mut registers = self.0.borrow_mut();
+        for (offset, &value) in data.iter().enumerate() {
+            let reg = reg + offset as u8;
+            if let Some((fail_reg, succeeding)) = &mut registers.fail_writes_to
+                && *fail_reg == reg
+            {
+                if *succeeding == 0 {
+                    return Err(MockError);
+                }
+                *succeeding -= 1;
+            }
+            registers.write(reg, value);
+        }
+        Ok(())
+    }
+}
+
+/// Delay that returns immediately
+pub(crate) struct NoDelay;
+
+impl embedded_hal::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -0,0 +1,218 @@
+use crate::{Error, PowerMode, constants, driver, interface};
+
+/// NVM access error
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NvmError {
+    /// Address outside of the NVM, or outside of the user rows for writes
+    InvalidAddress,
+    /// The sensor isn't in standby
+    NotStandby,
+    /// NVM busy with a previous operation
+    NotReady,
+    /// The operation failed
+    Failed,
+    /// The command sequence was rejected
+    CommandFailed,
+    /// The word read back differs from the one written
+    VerifyFailed,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(
+            asynch(sync = "blocking"),
+            embedded_hal_async(sync = "embedded_hal"),
+            Wait(sync = "InputPin")
+        )
+    ),
+    async(keep_self)
+)]
+impl<I, D, P, E> driver::asynch::Bmp5<I, D, P>
+where
+    I: interface::asynch::RegisterInterface<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    P: embedded_hal_async::digital::Wait,
+{
+    /// Read a 16 bit word from the NVM, addresses range from 0x00 to 0x3F.
+    ///
+    /// The sensor is briefly put into standby while the NVM is accessed.
+    pub async fn nvm_read(&mut self, addr: u8) -> Result<u16, Error<E>> {
+        if addr > constants::BMP5_NVM_ADDR_MASK {
+            return Err(Error::Nvm(NvmError::InvalidAddress));
+        }
+
+        let mode = self.suspend_for_update().await?;
+        let value = self.read_nvm_word(addr).await;
+        self.resume_after_update(mode, value).await
+    }
+
+    /// Program a 16 bit word into the user NVM rows, 0x20 to 0x22.
+    ///
+    /// The sensor needs to be in standby. The word is read back to verify
+    /// the write, NVM cells only endure a limited number of writes.
+    pub async fn nvm_write(&mut self, addr: u8, value: u16) -> Result<(), Error<E>> {
+        if !(constants::BMP5_NVM_START_ADDR..=constants::BMP5_NVM_END_ADDR).contains(&addr) {
+            return Err(Error::Nvm(NvmError::InvalidAddress));
+        }
+        if self.power_mode().await? != PowerMode::Standby {
+            return Err(Error::Nvm(NvmError::NotStandby));
+        }
+
+        self.write_regs(constants::BMP5_REG_NVM_DATA_LSB, &value.to_le_bytes())
+            .await?;
+        let result = self
+            .nvm_command(
+                addr | constants::BMP5_NVM_PROG_EN,
+                constants::BMP5_CMD_NVM_WRITE_SEQ_1,
+                constants::BMP5_NVM_WRITE_TIME_US,
+            )
+            .await;
+
+        // Disable programming again, whatever the outcome. The programming
+        // error takes precedence over the cleanup error.
+        let cleanup = self
+            .update_reg(constants::BMP5_REG_NVM_ADDR, constants::BMP5_NVM_PROG_EN, 0)
+            .await;
+        result?;
+        cleanup?;
+
+        if self.read_nvm_word(addr).await? != value {
+            return Err(Error::Nvm(NvmError::VerifyFailed));
+        }
+        Ok(())
+    }
+
+    async fn read_nvm_word(&mut self, addr: u8) -> Result<u16, Error<E>> {
+        self.nvm_command(
+            addr,
+            constants::BMP5_CMD_NVM_READ_SEQ_1,
+            constants::BMP5_NVM_READ_TIME_US,
+        )
+        .await?;
+
+        let mut buf = [0u8; 2];
+        self.read_regs(constants::BMP5_REG_NVM_DATA_LSB, &mut buf)
+            .await?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Run an NVM command on `nvm_addr`, the sensor must be in standby.
+    pub(crate) async fn nvm_command(
+        &mut self,
+        nvm_addr: u8,
+        cmd: u8,
+        duration_us: u32,
+    ) -> Result<(), Error<E>> {
+        let status = self.read_reg(constants::BMP5_REG_STATUS).await?;
+        if status & constants::BMP5_STATUS_NVM_RDY == 0 {
+            return Err(Error::Nvm(NvmError::NotReady));
+        }
+
+        self.update_reg(
+            constants::BMP5_REG_NVM_ADDR,
+            constants::BMP5_NVM_PROG_EN | constants::BMP5_NVM_ADDR_MASK,
+            nvm_addr,
+        )
+        .await?;
+        self.write_reg(constants::BMP5_REG_CMD, constants::BMP5_CMD_NVM_OP_SEQ_0)
+            .await?;
+        self.write_reg(constants::BMP5_REG_CMD, cmd).await?;
+
+        // Poll for completion, allowing twice the nominal duration
+        self.delay.delay_us(duration_us).await;
+        let mut remaining_us = duration_us;
+        let status = loop {
+            let status = self.read_reg(constants::BMP5_REG_STATUS).await?;
+            if status & constants::BMP5_STATUS_NVM_RDY != 0 {
+                break status;
+            }
+            if remaining_us == 0 {
+                return Err(Error::Timeout);
+            }
+            let delay_us = remaining_us.min(duration_us / 4);
+            self.delay.delay_us(delay_us).await;
+            remaining_us -= delay_us;
+        };
+
+        if status & constants::BMP5_STATUS_NVM_CMD_ERR != 0 {
+            Err(Error::Nvm(NvmError::CommandFailed))
+        } else if status & constants::BMP5_STATUS_NVM_ERR != 0 {
+            Err(Error::Nvm(NvmError::Failed))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NvmError;
+    use crate::mock::Mock;
+    use crate::{Config, Error, PowerMode, constants};
+
+    fn standby(mock: &Mock) -> crate::blocking::Bmp5<Mock, crate::mock::NoDelay, crate::NoPin> {
+        let mut sensor = mock.driver(Config::default());
+        sensor.init().unwrap();
+        sensor.set_power_mode(PowerMode::Standby).unwrap();
+        sensor
+    }
+
+    #[test]
+    fn nvm_write_verifies_the_word() {
+        let mock = Mock::new();
+        mock.0.borrow_mut().nvm_busy_reads = 2;
+        let mut sensor = standby(&mock);
+
+        sensor.nvm_write(0x21, 0xBEEF).unwrap();
+        assert_eq!(mock.0.borrow().nvm[0x21], 0xBEEF);
+        assert_eq!(sensor.nvm_read(0x21).unwrap(), 0xBEEF);
+        assert_eq!(
+            mock.reg(constants::BMP5_REG_NVM_ADDR) & constants::BMP5_NVM_PROG_EN,
+            0
+        );
+
+        mock.0.borrow_mut().nvm_corrupts = true;
+        assert!(matches!(
+            sensor.nvm_write(0x22, 0x1234),
+            Err(Error::Nvm(NvmError::VerifyFailed))
+        ));
+    }
+
+    #[test]
+    fn nvm_write_rejects_invalid_requests() {
+        let mock = Mock::new();
+        let mut sensor = mock.driver(Config::default());
+        sensor.init().unwrap();
+
+        assert!(matches!(
+            sensor.nvm_write(0x21, 0),
+            Err(Error::Nvm(NvmError::NotStandby))
+        ));
+        sensor.set_power_mode(PowerMode::Standby).unwrap();
+        assert!(matches!(
+            sensor.nvm_write(0x1F, 0),
+            Err(Error::Nvm(NvmError::InvalidAddress))
+        ));
+        assert_eq!(mock.0.borrow().nvm, [0; 64]);
+    }
+
+    #[test]
+    fn nvm_write_reports_the_programming_error_first() {
+        let mock = Mock::new();
+        let mut sensor = standby(&mock);
+        {
+            let mut registers = mock.0.borrow_mut();
+            registers.nvm_fails = true;
+            // Selecting the address succeeds, disabling programming fails
+            registers.fail_writes_to = Some((constants::BMP5_REG_NVM_ADDR, 1));
+        }
+
+        assert!(matches!(
+            sensor.nvm_write(0x20, 0x5555),
+            Err(Error::Nvm(NvmError::Failed))
+        ));
+        assert_eq!(mock.0.borrow().nvm[0x20], 0);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Error, InterruptSource, InterruptStatus, constants, driver, interface};
+
+/// Number of consecutive out-of-range samples before the interrupt is raised
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OorCountLimit {
+    /// Every out-of-range sample
+    #[default]
+    Count1,
+    /// 3 samples in a row
+    Count3,
+    /// 7 samples in a row
+    Count7,
+    /// 15 samples in a row
+    Count15,
+}
+
+impl From<OorCountLimit> for u8 {
+    fn from(count_limit: OorCountLimit) -> u8 {
+        match count_limit {
+            OorCountLimit::Count1 => 0,
+            OorCountLimit::Count3 => 1,
+            OorCountLimit::Count7 => 2,
+            OorCountLimit::Count15 => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for OorCountLimit {
+    type Error = u8;
+
+    /// Count limit for a register code, the invalid code is returned on failure.
+    fn try_from(code: u8) -> Result<Self, u8> {
+        match code {
+            0 => Ok(OorCountLimit::Count1),
+            1 => Ok(OorCountLimit::Count3),
+            2 => Ok(OorCountLimit::Count7),
+            3 => Ok(OorCountLimit::Count15),
+            code => Err(code),
+        }
+    }
+}
+
+/// Pressure out-of-range window
+///
+/// The pressure is out of range when it differs from `center` by more than
+/// `range`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OorConfig {
+    /// Centre of the window in Pascals, up to 131071
+    pub center: u32,
+    /// Half width of the window in Pascals
+    pub range: u8,
+    /// Persistence before the interrupt is raised
+    pub count_limit: OorCountLimit,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(
+            asynch(sync = "blocking"),
+            embedded_hal_async(sync = "embedded_hal"),
+            Wait(sync = "InputPin")
+        )
+    ),
+    async(keep_self)
+)]
+impl<I, D, P, E> driver::asynch::Bmp5<I, D, P>
+where
+    I: interface::asynch::RegisterInterface<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+    P: embedded_hal_async::digital::Wait,
+{
+    /// Program the pressure out-of-range window and enable its interrupt.
+    ///
+    /// The sensor is briefly put into standby while the window is written.
+    /// Out-of-range detection only runs while the sensor is measuring.
+    pub async fn set_pressure_oor(&mut self, oor: OorConfig) -> Result<(), Error<E>> {
+        if oor.center > constants::BMP5_OOR_MAX_THRESHOLD {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mode = self.suspend_for_update().await?;
+        self.config.pressure_oor = oor;
+        let result = match self.write_oor_config().await {
+            Ok(()) => {
+                self.enable_interrupts(InterruptSource::PRESSURE_OUT_OF_RANGE)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        self.resume_after_update(mode, result).await
+    }
+
+    /// Wait until the pressure leaves the out-of-range window.
+    ///
+    /// [`InterruptSource::PRESSURE_OUT_OF_RANGE`] needs to be enabled, see
+    /// [`set_pressure_oor`](Self::set_pressure_oor).
+    pub async fn wait_for_pressure_oor(&mut self) -> Result<(), Error<E>> {
+        if !self
+            .config
+            .interrupt_source
+            .contains(InterruptSource::PRESSURE_OUT_OF_RANGE)
+        {
+            return Err(Error::InvalidConfig);
+        }
+
+        // A new sample can't arrive faster than once per ODR period
+        self.wait_for_interrupt(
+            InterruptStatus::PRESSURE_OUT_OF_RANGE,
+            self.config.output_data_rate.period_us(),
+            self.config.timeout_us,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn write_oor_config(&mut self) -> Result<(), Error<E>> {
+        let oor = self.config.pressure_oor;
+        if oor.center > constants::BMP5_OOR_MAX_THRESHOLD {
+            return Err(Error::InvalidConfig);
+        }
+
+        let oor_config = self.read_reg(constants::BMP5_REG_OOR_CONFIG).await?;
+        let oor_config = (oor_config
+            & !(constants::BMP5_OOR_COUNT_LIMIT_MASK | constants::BMP5_OOR_THR_P_XMSB_MASK))
+            | (u8::from(oor.count_limit) << constants::BMP5_OOR_COUNT_LIMIT_POS)
+            | (oor.center >> 16) as u8;
+
+        // Threshold, range and config are consecutive registers
+        let buf = [
+            oor.center as u8,
+            (oor.center >> 8) as u8,
+            oor.range,
+            oor_config,
+        ];
+        self.write_regs(constants::BMP5_REG_OOR_THR_P_LSB, &buf)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_limit_round_trip() {
+        crate::tests::assert_round_trip::<OorCountLimit>(4);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{Config, constants, driver, interface};
+
+/// BMP5 driver connected over SPI
+pub type Bmp5<SPI, D, P = crate::NoPin> = crate::Bmp5<SpiInterface<SPI>, D, P>;
+
+pub mod blocking {
+    use super::SpiInterface;
+
+    /// Blocking BMP5 driver connected over SPI
+    pub type Bmp5<SPI, D, P = crate::NoPin> = crate::blocking::Bmp5<SpiInterface<SPI>, D, P>;
+}
+
+/// SPI register interface
+pub struct SpiInterface<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> SpiInterface<SPI> {
+    pub fn new(spi: SPI) -> Self {
+        Self { spi }
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(asynch(sync = "blocking"), embedded_hal_async(sync = "embedded_hal"))
+    ),
+    async(keep_self)
+)]
+impl<SPI, E> interface::asynch::RegisterInterface for SpiInterface<SPI>
+where
+    SPI: embedded_hal_async::spi::SpiDevice<Error = E>,
+{
+    type Error = E;
+
+    async fn read_registers(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), E> {
+        use embedded_hal_async::spi::Operation;
+
+        self.spi
+            .transaction(&mut [
+                Operation::Write(&[reg | constants::BMP5_SPI_RD_MASK]),
+                Operation::Read(buf),
+            ])
+            .await
+    }
+
+    async fn write_registers(&mut self, reg: u8, data: &[u8]) -> Result<(), E> {
+        use embedded_hal_async::spi::Operation;
+
+        self.spi
+            .transaction(&mut [
+                Operation::Write(&[reg & constants::BMP5_SPI_WR_MASK]),
+                Operation::Write(data),
+            ])
+            .await
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        keep_self,
+        idents(asynch(sync = "blocking"), embedded_hal_async(sync = "embedded_hal"))
+    ),
+    async(keep_self)
+)]
+impl<SPI, D, E> driver::asynch::Bmp5<SpiInterface<SPI>, D>
+where
+    SPI: embedded_hal_async::spi::SpiDevice<Error = E>,
+    D: embedded_hal_async::delay::DelayNs,
+{
+    pub fn new(spi: SPI, delay: D, config: Config) -> Self {
+        Self::from_interface(SpiInterface::new(spi), delay, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpiInterface;
+    use crate::blocking::RegisterInterface;
+    use crate::constants;
+    use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+    /// Records the first byte of every transaction
+    #[derive(Default)]
+    struct AddressLog(Vec<u8>);
+
+    impl ErrorType for AddressLog {
+        type Error = core::convert::Infallible;
+    }
+
+    impl SpiDevice for AddressLog {
+        fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+            if let [Operation::Write(address), ..] = operations {
+                self.0.push(address[0]);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn address_carries_the_read_bit() {
+        let mut interface = SpiInterface::new(AddressLog::default());
+        interface
+            .read_register(constants::BMP5_REG_INT_STATUS)
+            .unwrap();
+        interface
+            .write_register(constants::BMP5_REG_CMD, constants::BMP5_CMD_SOFT_RESET)
+            .unwrap();
+        interface.write_register(0xFF, 0).unwrap();
+        assert_eq!(interface.spi.0, [0xA7, 0x7E, 0x7F]);
+    }
+}
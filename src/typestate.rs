@@ -0,0 +1,293 @@
+//! Driver wrapper that tracks initialization and power mode in its type
+//!
+//! Methods are only available in the states where they make sense, e.g.
+//! `measure` can't be called before `init`, or while the sensor is in
+//! standby. Mode transitions consume the driver and return it in the new state.
+//!
+//! When a transition fails the sensor may have been left in any mode, e.g.
+//! standby is entered before the new mode is written. The driver is handed
+//! back in the [`Unknown`] state together with the error, from which another
+//! transition brings it back to a known mode. A failed `init` hands the driver
+//! back uninitialized.
+//!
+//! Sequences that don't make sense don't compile:
+//!
+//! ```
+//! use bmp5::blocking::{RegisterInterface, typestate::Bmp5};
+//! use bmp5::typestate::{Normal, Standby};
+//! use embedded_hal::delay::DelayNs;
+//!
+//! fn normal<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Normal>) {
+//!     let _ = sensor.measure();
+//! }
+//!
+//! fn standby<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Standby>) {
+//!     let _ = sensor.measure_forced();
+//! }
+//! ```
+//!
+//! Measuring before `init`:
+//!
+//! ```compile_fail
+//! use bmp5::blocking::{RegisterInterface, typestate::Bmp5};
+//! use bmp5::typestate::Uninit;
+//! use embedded_hal::delay::DelayNs;
+//!
+//! fn uninit<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Uninit>) {
+//!     let _ = sensor.measure();
+//! }
+//! ```
+//!
+//! A forced measurement in normal mode:
+//!
+//! ```compile_fail
+//! use bmp5::blocking::{RegisterInterface, typestate::Bmp5};
+//! use bmp5::typestate::Normal;
+//! use embedded_hal::delay::DelayNs;
+//!
+//! fn normal<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Normal>) {
+//!     let _ = sensor.measure_forced();
+//! }
+//! ```
+//!
+//! Measuring in standby:
+//!
+//! ```compile_fail
+//! use bmp5::blocking::{RegisterInterface, typestate::Bmp5};
+//! use bmp5::typestate::Standby;
+//! use embedded_hal::delay::DelayNs;
+//!
+//! fn standby<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Standby>) {
+//!     let _ = sensor.measure();
+//! }
+//! ```
+//!
+//! Measuring after a failed transition:
+//!
+//! ```compile_fail
+//! use bmp5::blocking::{RegisterInterface, typestate::Bmp5};
+//! use bmp5::typestate::Unknown;
+//! use embedded_hal::delay::DelayNs;
+//!
+//! fn unknown<I: RegisterInterface, D: DelayNs>(sensor: &mut Bmp5<I, D, Unknown>) {
+//!     let _ = sensor.measure();
+//! }
+//! ```
+
+pub use asynch::Bmp5;
+
+/// Driver has not been initialized yet
+pub struct Uninit;
+
+/// Sensor is in standby
+pub struct Standby;
+
+/// Sensor is in deep standby
+pub struct DeepStandby;
+
+/// Sensor is measuring at the configured output data rate
+pub struct Normal;
+
+/// Sensor is taking a single forced measurement
+pub struct Forced;
+
+/// Sensor is measuring back to back
+pub struct Continuous;
+
+/// Sensor mode is unknown after a failed transition
+pub struct Unknown;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Standby {}
+    impl Sealed for super::DeepStandby {}
+    impl Sealed for super::Normal {}
+    impl Sealed for super::Forced {}
+    impl Sealed for super::Continuous {}
+    impl Sealed for super::Unknown {}
+}
+
+/// States of an initialized driver
+pub trait Initialized: sealed::Sealed {}
+
+impl Initialized for Standby {}
+impl Initialized for DeepStandby {}
+impl Initialized for Normal {}
+impl Initialized for Forced {}
+impl Initialized for Continuous {}
+impl Initialized for Unknown {}
+
+/// States in which the sensor produces measurements on its own
+pub trait Measuring: Initialized {}
+
+impl Measuring for Normal {}
+impl Measuring for Continuous {}
+
+#[maybe_async_cfg::maybe(
+    sync(
+        self = "blocking",
+        idents(embedded_hal_async(sync = "embedded_hal"), Wait(sync = "InputPin"))
+    ),
+    async(keep_self)
+)]
+pub mod asynch {
+    use super::{
+        Continuous, DeepStandby, Forced, Initialized, Measuring, Normal, Standby, Uninit, Unknown,
+    };
+    use crate::interface::asynch::RegisterInterface;
+    use crate::{Error, Measurement, NoPin, PowerMode, driver};
+    use core::marker::PhantomData;
+
+    /// BMP5 driver with its state encoded in the type
+    pub struct Bmp5<I, D, S, P = NoPin> {
+        inner: driver::asynch::Bmp5<I, D, P>,
+        _state: PhantomData<S>,
+    }
+
+    impl<I, D, S, P> Bmp5<I, D, S, P> {
+        /// Release the underlying driver.
+        pub fn into_inner(self) -> driver::asynch::Bmp5<I, D, P> {
+            self.inner
+        }
+
+        fn into_state<T>(self) -> Bmp5<I, D, T, P> {
+            Bmp5 {
+                inner: self.inner,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    impl<I, D, P, E> Bmp5<I, D, Uninit, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+    {
+        pub fn new(inner: driver::asynch::Bmp5<I, D, P>) -> Self {
+            Self {
+                inner,
+                _state: PhantomData,
+            }
+        }
+
+        /// Initialize the sensor, leaving it in normal mode.
+        #[allow(clippy::type_complexity)]
+        pub async fn init(mut self) -> Result<Bmp5<I, D, Normal, P>, (Error<E>, Self)> {
+            match self.inner.init().await {
+                Ok(()) => Ok(self.into_state()),
+                Err(err) => Err((err, self)),
+            }
+        }
+    }
+
+    impl<I, D, P, E, S> Bmp5<I, D, S, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+        S: Initialized,
+    {
+        #[allow(clippy::type_complexity)]
+        pub async fn into_standby(
+            self,
+        ) -> Result<Bmp5<I, D, Standby, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            self.transition(PowerMode::Standby).await
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub async fn into_deep_standby(
+            self,
+        ) -> Result<Bmp5<I, D, DeepStandby, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            self.transition(PowerMode::DeepStandby).await
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub async fn into_normal(
+            self,
+        ) -> Result<Bmp5<I, D, Normal, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            self.transition(PowerMode::Normal).await
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub async fn into_continuous(
+            self,
+        ) -> Result<Bmp5<I, D, Continuous, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            self.transition(PowerMode::Continuous).await
+        }
+
+        /// Trigger a single conversion, collect the result with `measure`.
+        #[allow(clippy::type_complexity)]
+        pub async fn into_forced(
+            mut self,
+        ) -> Result<Bmp5<I, D, Forced, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            match self.inner.start_forced().await {
+                Ok(()) => Ok(self.into_state()),
+                Err(err) => Err((err, self.into_state())),
+            }
+        }
+
+        #[allow(clippy::type_complexity)]
+        async fn transition<T>(
+            mut self,
+            mode: PowerMode,
+        ) -> Result<Bmp5<I, D, T, P>, (Error<E>, Bmp5<I, D, Unknown, P>)> {
+            match self.inner.set_power_mode(mode).await {
+                Ok(()) => Ok(self.into_state()),
+                Err(err) => Err((err, self.into_state())),
+            }
+        }
+    }
+
+    impl<I, D, P, E, S> Bmp5<I, D, S, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+        S: Measuring,
+    {
+        pub async fn measure(&mut self) -> Result<Measurement, Error<E>> {
+            self.inner.measure().await
+        }
+
+        pub async fn measure_with_timeout(
+            &mut self,
+            timeout_us: u32,
+        ) -> Result<Measurement, Error<E>> {
+            self.inner.measure_with_timeout(timeout_us).await
+        }
+    }
+
+    impl<I, D, P, E> Bmp5<I, D, Standby, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+    {
+        /// Take a single measurement, returning to standby afterwards.
+        pub async fn measure_forced(&mut self) -> Result<Measurement, Error<E>> {
+            self.inner.measure_forced().await
+        }
+    }
+
+    impl<I, D, P, E> Bmp5<I, D, Forced, P>
+    where
+        I: RegisterInterface<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+        P: embedded_hal_async::digital::Wait,
+    {
+        /// Wait for the forced conversion to complete and read it, the sensor
+        /// is back in standby afterwards.
+        #[allow(clippy::type_complexity)]
+        pub async fn measure(
+            mut self,
+        ) -> Result<(Measurement, Bmp5<I, D, Standby, P>), (Error<E>, Bmp5<I, D, Unknown, P>)>
+        {
+            match self.inner.finish_forced().await {
+                Ok(measurement) => Ok((measurement, self.into_state())),
+                Err(err) => Err((err, self.into_state())),
+            }
+        }
+    }
+}